@@ -0,0 +1,410 @@
+use super::{
+    damage::DamageCalc,
+    data::{AbilitiesEntry, SpeciesAbilitiesEntry},
+    monster::{Monster, MonsterAttribute, MonsterStatus},
+    moves::{MoveCategory, PokemonMove},
+    ElementType,
+};
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub const LIMBER: u16 = 7;
+pub const STATIC: u16 = 9;
+pub const VOLT_ABSORB: u16 = 10;
+pub const WATER_ABSORB: u16 = 11;
+pub const FLASH_FIRE: u16 = 18;
+pub const INTIMIDATE: u16 = 22;
+pub const WONDER_GUARD: u16 = 25;
+pub const LEVITATE: u16 = 26;
+pub const CLEAR_BODY: u16 = 29;
+pub const HUGE_POWER: u16 = 37;
+pub const THICK_FAT: u16 = 47;
+pub const HYPER_CUTTER: u16 = 52;
+pub const HUSTLE: u16 = 55;
+pub const GUTS: u16 = 62;
+pub const OVERGROW: u16 = 65;
+pub const BLAZE: u16 = 66;
+pub const TORRENT: u16 = 67;
+pub const SWARM: u16 = 68;
+pub const WHITE_SMOKE: u16 = 73;
+pub const PURE_POWER: u16 = 74;
+pub const MOTOR_DRIVE: u16 = 78;
+pub const DRY_SKIN: u16 = 87;
+pub const SUPER_LUCK: u16 = 105;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PokemonAbility {
+    pub id: usize,
+    pub name: String,
+}
+
+impl PokemonAbility {
+    pub fn from_json(obj: &AbilitiesEntry) -> Self {
+        Self {
+            id: obj.id as usize,
+            name: obj.name.english.clone(),
+        }
+    }
+    pub fn from_entries(entries: &[AbilitiesEntry]) -> HashMap<usize, Self> {
+        let mut map: HashMap<usize, PokemonAbility> = HashMap::new();
+        for obj in entries.iter() {
+            let entry = PokemonAbility::from_json(obj);
+            map.insert(entry.id, entry);
+        }
+        map
+    }
+}
+
+/// The abilities a species can have.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AbilitySlots {
+    /// The one or two regular abilities, empty when no ability data is available.
+    pub regular: Vec<u16>,
+    pub hidden: Option<u16>,
+}
+
+impl AbilitySlots {
+    pub fn from_json(obj: &SpeciesAbilitiesEntry) -> Self {
+        Self {
+            regular: obj.abilities.iter().map(|id| *id as u16).collect(),
+            hidden: obj.hidden.map(|id| id as u16),
+        }
+    }
+    pub fn from_entries(entries: &[SpeciesAbilitiesEntry]) -> HashMap<usize, Self> {
+        let mut map: HashMap<usize, AbilitySlots> = HashMap::new();
+        for obj in entries.iter() {
+            map.insert(obj.id as usize, AbilitySlots::from_json(obj));
+        }
+        map
+    }
+    /// Pick one of the regular abilities at random.
+    pub fn generate<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<u16> {
+        self.regular.choose(rng).copied()
+    }
+    /// Whether `ability` is one of this species' abilities.
+    pub fn contains(&self, ability: u16) -> bool {
+        self.regular.contains(&ability) || self.hidden == Some(ability)
+    }
+}
+
+/// An effect triggered when a monster enters battle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SwitchInEffect {
+    /// Change a stat stage of the opposing monster.
+    FoeStatStage(MonsterAttribute, i8),
+}
+
+impl SwitchInEffect {
+    /// Apply this effect to the opposing monster.
+    /// Returns false when the foe's ability blocked it.
+    pub fn apply(&self, foe: &mut Monster) -> bool {
+        match self {
+            SwitchInEffect::FoeStatStage(attr, stages) => {
+                if *stages < 0 && foe.ability_hooks().blocks_stat_drop(attr) {
+                    return false;
+                }
+                foe.combat_mods.apply_stages(attr.clone(), *stages);
+                true
+            }
+        }
+    }
+}
+
+/// An effect triggered when a move makes contact with a monster.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ContactEffect {
+    /// Inflict a major status on the attacker, with a percent chance.
+    AttackerStatus(MonsterStatus, u8),
+}
+
+/// Hooks through which an ability modifies battle mechanics.
+/// Every hook defaults to having no effect.
+pub trait AbilityHooks: Sync {
+    /// Modify one of the holder's battle stats, after stat stages.
+    fn modify_stat(&self, _holder: &Monster, _attr: &MonsterAttribute, value: u16) -> u16 {
+        value
+    }
+    /// Modify the damage inputs of a move used by the holder.
+    fn on_attack(&self, _holder: &Monster, _mv: &PokemonMove, _calc: &mut DamageCalc) {}
+    /// Modify the damage inputs of a move used against the holder.
+    fn on_defend(&self, _holder: &Monster, _mv: &PokemonMove, _calc: &mut DamageCalc) {}
+    /// Whether the holder is unaffected by a move with the given type effectiveness.
+    fn is_immune(&self, _holder: &Monster, _mv: &PokemonMove, _effectiveness: f32) -> bool {
+        false
+    }
+    /// Whether the holder can't be given a major status.
+    fn blocks_status(&self, _status: MonsterStatus) -> bool {
+        false
+    }
+    /// The effect on a monster whose move made contact with the holder.
+    fn on_contact(&self, _holder: &Monster) -> Option<ContactEffect> {
+        None
+    }
+    /// Whether the holder's stat can't be lowered by other monsters.
+    fn blocks_stat_drop(&self, _attr: &MonsterAttribute) -> bool {
+        false
+    }
+    /// Modify the accuracy of a move used by the holder, before accuracy and evasion stages.
+    fn modify_accuracy(&self, _holder: &Monster, _mv: &PokemonMove, accuracy: u8) -> u8 {
+        accuracy
+    }
+    /// Critical hit stages added to the holder's moves.
+    fn crit_stage_bonus(&self) -> u8 {
+        0
+    }
+    /// Effects triggered when the holder enters battle.
+    fn on_switch_in(&self, _holder: &Monster) -> Vec<SwitchInEffect> {
+        Vec::new()
+    }
+}
+
+struct NoAbility;
+impl AbilityHooks for NoAbility {}
+
+struct Intimidate;
+impl AbilityHooks for Intimidate {
+    fn on_switch_in(&self, _holder: &Monster) -> Vec<SwitchInEffect> {
+        vec![SwitchInEffect::FoeStatStage(MonsterAttribute::ATT, -1)]
+    }
+}
+
+/// Immunity to every move of one element, like Levitate or Volt Absorb.
+struct ElementImmunity(ElementType);
+impl AbilityHooks for ElementImmunity {
+    fn is_immune(&self, _holder: &Monster, mv: &PokemonMove, _effectiveness: f32) -> bool {
+        mv.element == self.0
+    }
+}
+
+struct WonderGuard;
+impl AbilityHooks for WonderGuard {
+    fn is_immune(&self, _holder: &Monster, mv: &PokemonMove, effectiveness: f32) -> bool {
+        mv.category != MoveCategory::Status && effectiveness < 2f32
+    }
+}
+
+/// Multiplies Attack by a fraction, like Huge Power.
+struct AttackBoost(u32, u32);
+impl AbilityHooks for AttackBoost {
+    fn modify_stat(&self, _holder: &Monster, attr: &MonsterAttribute, value: u16) -> u16 {
+        if *attr == MonsterAttribute::ATT {
+            (value as u32 * self.0 / self.1) as u16
+        } else {
+            value
+        }
+    }
+}
+
+/// 1.5x Attack, at the cost of 0.8x accuracy on physical moves.
+struct Hustle;
+impl AbilityHooks for Hustle {
+    fn modify_stat(&self, holder: &Monster, attr: &MonsterAttribute, value: u16) -> u16 {
+        AttackBoost(3, 2).modify_stat(holder, attr, value)
+    }
+    fn modify_accuracy(&self, _holder: &Monster, mv: &PokemonMove, accuracy: u8) -> u8 {
+        if mv.category == MoveCategory::Physical {
+            (accuracy as u32 * 4 / 5) as u8
+        } else {
+            accuracy
+        }
+    }
+}
+
+struct Guts;
+impl AbilityHooks for Guts {
+    fn modify_stat(&self, holder: &Monster, attr: &MonsterAttribute, value: u16) -> u16 {
+        let statused = holder.combat_status.intersects(
+            MonsterStatus::BRN
+                | MonsterStatus::FRZ
+                | MonsterStatus::PAR
+                | MonsterStatus::PSN
+                | MonsterStatus::SLP
+                | MonsterStatus::TOX,
+        );
+        if statused && *attr == MonsterAttribute::ATT {
+            (value as u32 * 3 / 2) as u16
+        } else {
+            value
+        }
+    }
+    fn on_attack(&self, _holder: &Monster, _mv: &PokemonMove, calc: &mut DamageCalc) {
+        calc.burned = false;
+    }
+}
+
+/// Boosts moves of one element at 1/3 HP or less, like Overgrow.
+struct Pinch(ElementType);
+impl AbilityHooks for Pinch {
+    fn on_attack(&self, holder: &Monster, mv: &PokemonMove, calc: &mut DamageCalc) {
+        if mv.element == self.0 && holder.hp as u32 * 3 <= holder.max_hp() as u32 {
+            calc.power = calc.power * 3 / 2;
+        }
+    }
+}
+
+struct ThickFat;
+impl AbilityHooks for ThickFat {
+    fn on_defend(&self, _holder: &Monster, mv: &PokemonMove, calc: &mut DamageCalc) {
+        if mv.element == ElementType::Fire || mv.element == ElementType::Ice {
+            calc.attack /= 2;
+        }
+    }
+}
+
+/// Prevents other monsters from lowering a stat, or every stat with `None`.
+struct StatGuard(Option<MonsterAttribute>);
+impl AbilityHooks for StatGuard {
+    fn blocks_stat_drop(&self, attr: &MonsterAttribute) -> bool {
+        self.0
+            .as_ref()
+            .map(|guarded| guarded == attr)
+            .unwrap_or(true)
+    }
+}
+
+/// Prevents one major status, like Limber.
+struct StatusImmunity(MonsterStatus);
+impl AbilityHooks for StatusImmunity {
+    fn blocks_status(&self, status: MonsterStatus) -> bool {
+        status == self.0
+    }
+}
+
+/// 30% chance to paralyze a monster that touches the holder.
+struct Static;
+impl AbilityHooks for Static {
+    fn on_contact(&self, _holder: &Monster) -> Option<ContactEffect> {
+        Some(ContactEffect::AttackerStatus(MonsterStatus::PAR, 30))
+    }
+}
+
+struct SuperLuck;
+impl AbilityHooks for SuperLuck {
+    fn crit_stage_bonus(&self) -> u8 {
+        1
+    }
+}
+
+static NO_ABILITY: NoAbility = NoAbility;
+static INTIMIDATE_HOOKS: Intimidate = Intimidate;
+static LEVITATE_HOOKS: ElementImmunity = ElementImmunity(ElementType::Ground);
+static ELECTRIC_IMMUNITY: ElementImmunity = ElementImmunity(ElementType::Electric);
+static WATER_IMMUNITY: ElementImmunity = ElementImmunity(ElementType::Water);
+static FIRE_IMMUNITY: ElementImmunity = ElementImmunity(ElementType::Fire);
+static WONDER_GUARD_HOOKS: WonderGuard = WonderGuard;
+static DOUBLE_ATTACK: AttackBoost = AttackBoost(2, 1);
+static HUSTLE_HOOKS: Hustle = Hustle;
+static GUTS_HOOKS: Guts = Guts;
+static OVERGROW_HOOKS: Pinch = Pinch(ElementType::Grass);
+static BLAZE_HOOKS: Pinch = Pinch(ElementType::Fire);
+static TORRENT_HOOKS: Pinch = Pinch(ElementType::Water);
+static SWARM_HOOKS: Pinch = Pinch(ElementType::Bug);
+static THICK_FAT_HOOKS: ThickFat = ThickFat;
+static HYPER_CUTTER_HOOKS: StatGuard = StatGuard(Some(MonsterAttribute::ATT));
+static CLEAR_BODY_HOOKS: StatGuard = StatGuard(None);
+static LIMBER_HOOKS: StatusImmunity = StatusImmunity(MonsterStatus::PAR);
+static STATIC_HOOKS: Static = Static;
+static SUPER_LUCK_HOOKS: SuperLuck = SuperLuck;
+
+/// Get the hooks implementing an ability.
+/// Abilities without implemented mechanics have no effect.
+pub fn get_hooks(ability: Option<u16>) -> &'static dyn AbilityHooks {
+    match ability {
+        Some(INTIMIDATE) => &INTIMIDATE_HOOKS,
+        Some(LEVITATE) => &LEVITATE_HOOKS,
+        Some(VOLT_ABSORB) | Some(MOTOR_DRIVE) => &ELECTRIC_IMMUNITY,
+        Some(WATER_ABSORB) | Some(DRY_SKIN) => &WATER_IMMUNITY,
+        Some(FLASH_FIRE) => &FIRE_IMMUNITY,
+        Some(WONDER_GUARD) => &WONDER_GUARD_HOOKS,
+        Some(HUGE_POWER) | Some(PURE_POWER) => &DOUBLE_ATTACK,
+        Some(HUSTLE) => &HUSTLE_HOOKS,
+        Some(GUTS) => &GUTS_HOOKS,
+        Some(OVERGROW) => &OVERGROW_HOOKS,
+        Some(BLAZE) => &BLAZE_HOOKS,
+        Some(TORRENT) => &TORRENT_HOOKS,
+        Some(SWARM) => &SWARM_HOOKS,
+        Some(THICK_FAT) => &THICK_FAT_HOOKS,
+        Some(HYPER_CUTTER) => &HYPER_CUTTER_HOOKS,
+        Some(CLEAR_BODY) | Some(WHITE_SMOKE) => &CLEAR_BODY_HOOKS,
+        Some(LIMBER) => &LIMBER_HOOKS,
+        Some(STATIC) => &STATIC_HOOKS,
+        Some(SUPER_LUCK) => &SUPER_LUCK_HOOKS,
+        _ => &NO_ABILITY,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game::{damage::DamageCalc, dex::Dex, monster::Monster};
+
+    #[test]
+    fn species_abilities() {
+        let bulbasaur = Monster::from_dex(1);
+        assert_eq!(bulbasaur.ability, Some(OVERGROW));
+        assert_eq!(bulbasaur.get_ability().unwrap().name, "Overgrow");
+        let mawile = Monster::from_dex(303);
+        assert!(mawile.ability == Some(HYPER_CUTTER) || mawile.ability == Some(INTIMIDATE));
+        let mimikyu = Monster::from_dex(778);
+        assert_eq!(mimikyu.get_ability().unwrap().name, "Disguise");
+        let dex = Dex::global().unwrap();
+        assert!(dex
+            .species
+            .values()
+            .all(|species| !species.abilities.regular.is_empty()));
+    }
+
+    #[test]
+    fn ability_hooks() {
+        let mut gyarados = Monster::from_dex(130);
+        gyarados.set_level(50);
+        let mut mawile = Monster::from_dex(303);
+        mawile.set_level(50);
+        mawile.ability = Some(HYPER_CUTTER);
+        let mut machop = Monster::from_dex(66);
+        machop.ability = Some(GUTS);
+        for effect in gyarados.ability_hooks().on_switch_in(&gyarados) {
+            assert!(!effect.apply(&mut mawile));
+            assert!(effect.apply(&mut machop));
+        }
+        assert_eq!(machop.combat_mods.internal[&MonsterAttribute::ATT], -1);
+
+        let mut gastly = Monster::from_dex(92);
+        gastly.set_level(50);
+        let earthquake = PokemonMove::get_move(89).unwrap();
+        assert_eq!(gastly.ability, Some(LEVITATE));
+        assert_eq!(
+            DamageCalc::new(&gyarados, &gastly, &earthquake, false)
+                .unwrap()
+                .roll(100),
+            0
+        );
+
+        // Shedinja only takes damage from super effective moves.
+        let mut shedinja = Monster::from_dex(292);
+        shedinja.set_level(50);
+        let water_gun = PokemonMove::get_move(55).unwrap();
+        let ember = PokemonMove::get_move(52).unwrap();
+        assert_eq!(shedinja.max_hp(), 1);
+        assert_eq!(
+            gyarados.deal_damage(&mut thread_rng(), &shedinja, &water_gun, false),
+            0
+        );
+        assert!(gyarados.deal_damage(&mut thread_rng(), &shedinja, &ember, false) > 0);
+
+        // Hustle trades physical accuracy for Attack.
+        let mut hustler = Monster::from_dex(66);
+        hustler.set_level(50);
+        let attack = hustler.get_battle_stat(MonsterAttribute::ATT);
+        hustler.ability = Some(HUSTLE);
+        assert_eq!(
+            hustler.get_battle_stat(MonsterAttribute::ATT),
+            attack * 3 / 2
+        );
+        let hooks = hustler.ability_hooks();
+        let tackle = PokemonMove::get_move(33).unwrap();
+        assert_eq!(hooks.modify_accuracy(&hustler, &tackle, 100), 80);
+        assert_eq!(hooks.modify_accuracy(&hustler, &ember, 100), 100);
+    }
+}
@@ -0,0 +1,1514 @@
+use super::{
+    abilities::{ContactEffect, SwitchInEffect},
+    critical::CritMechanic,
+    damage::{DamageCalc, DamageMechanic},
+    dex::Dex,
+    events::BattleEvent,
+    monster::{Monster, MonsterAttribute, MonsterStages, MonsterStatus, ParalysisMechanic},
+    moves::{CategoryMechanic, MoveEffect, PriorityMechanic},
+    replay::{Replay, ReplayStep, REPLAY_VERSION},
+    rng::BattleRng,
+    ElementType,
+};
+use enumflags2::BitFlags;
+use serde::{Deserialize, Serialize};
+
+pub const POTION: u16 = 17;
+pub const ANTIDOTE: u16 = 18;
+pub const BURN_HEAL: u16 = 19;
+pub const ICE_HEAL: u16 = 20;
+pub const AWAKENING: u16 = 21;
+pub const PARALYZE_HEAL: u16 = 22;
+pub const FULL_RESTORE: u16 = 23;
+pub const MAX_POTION: u16 = 24;
+pub const HYPER_POTION: u16 = 25;
+pub const SUPER_POTION: u16 = 26;
+pub const FULL_HEAL: u16 = 27;
+pub const REVIVE: u16 = 28;
+pub const MAX_REVIVE: u16 = 29;
+
+/// How many turns Trick Room lasts, including the turn it is used.
+pub const TRICK_ROOM_TURNS: u8 = 5;
+
+/// The chance of a paralyzed monster being unable to move, as 1 in this many.
+pub const FULL_PARALYSIS_CHANCE: u32 = 4;
+
+/// The most turns a monster sleeps for.
+pub const MAX_SLEEP_TURNS: u8 = 3;
+
+/// The move used instead of the chosen one once every move is out of PP.
+pub const STRUGGLE: usize = 165;
+
+pub mod error {
+    use crate::game::data::error::DataError;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum BattleError {
+        /// A move is missing from the data.
+        Data(DataError),
+        /// The battle already has an outcome.
+        BattleOver,
+        /// The side's active monster fainted and must be replaced first.
+        MustSwitch(usize),
+        /// The side's active monster hasn't fainted, so there is nothing to replace.
+        NoSwitchNeeded(usize),
+        /// The move slot is empty or out of range.
+        InvalidMove(usize),
+        /// The move in this slot has no PP left.
+        NoPp(usize),
+        /// The team slot is out of range, fainted or already active.
+        InvalidSwitch(usize),
+        ItemNotInBag(u16),
+        /// The item would have no effect on the target.
+        UnusableItem(u16),
+        InvalidTarget(usize),
+        /// Running is only possible in wild battles.
+        CannotRun,
+        /// The dex resolves move categories or priorities differently from the dex
+        /// used for earlier turns.
+        MechanicsChanged,
+    }
+
+    impl std::fmt::Display for BattleError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            let msg = match self {
+                BattleError::Data(e) => e.to_string(),
+                BattleError::BattleOver => "The battle is over.".to_string(),
+                BattleError::MustSwitch(side) => {
+                    format!("Side {} must replace its fainted monster.", side)
+                }
+                BattleError::NoSwitchNeeded(side) => {
+                    format!("Side {} has no fainted monster to replace.", side)
+                }
+                BattleError::InvalidMove(slot) => format!("No move in slot {}.", slot),
+                BattleError::NoPp(slot) => format!("The move in slot {} has no PP left.", slot),
+                BattleError::InvalidSwitch(index) => {
+                    format!("Can't switch to team slot {}.", index)
+                }
+                BattleError::ItemNotInBag(item) => format!("Item {} is not in the bag.", item),
+                BattleError::UnusableItem(item) => format!("Item {} would have no effect.", item),
+                BattleError::InvalidTarget(index) => format!("No monster in team slot {}.", index),
+                BattleError::CannotRun => "Can't run from a trainer battle.".to_string(),
+                BattleError::MechanicsChanged => {
+                    "The dex uses different move rules from earlier turns.".to_string()
+                }
+            };
+            write!(f, "{}", msg)
+        }
+    }
+
+    impl std::error::Error for BattleError {}
+
+    impl From<DataError> for BattleError {
+        fn from(e: DataError) -> Self {
+            BattleError::Data(e)
+        }
+    }
+}
+
+pub mod result {
+    pub type Result<T> = std::result::Result<T, super::error::BattleError>;
+}
+
+use error::BattleError;
+
+/// What a side does during a turn.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+    /// Use the move in one of the active monster's four slots.
+    /// Once every move is out of PP, any slot uses Struggle instead.
+    Move(usize),
+    /// Switch the active monster for another team member.
+    Switch(usize),
+    /// Use an item from the bag on a team member.
+    UseItem { item: u16, target: usize },
+    /// Try to flee a wild battle.
+    Run,
+}
+
+impl Action {
+    /// Actions resolve in this order before speed is considered:
+    /// fleeing, then switching, then items, then moves.
+    fn bracket(&self) -> u8 {
+        match self {
+            Action::Run => 0,
+            Action::Switch(_) => 1,
+            Action::UseItem { .. } => 2,
+            Action::Move(_) => 3,
+        }
+    }
+}
+
+/// How a battle ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BattleOutcome {
+    /// Every monster on the other side fainted.
+    Winner(usize),
+    /// This side fled.
+    Fled(usize),
+    /// The last monsters on both sides fainted in the same turn, as when a
+    /// Struggle user's recoil faints it along with its foe.
+    Draw,
+}
+
+/// One side of a battle: a team, the monster it has out, the PP left on each
+/// team member's moves and the items it can use.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BattleSide {
+    pub team: Vec<Monster>,
+    /// The index of the active monster in `team`.
+    pub active: usize,
+    /// The PP left on each team member's moves, indexed like `team`.
+    pub pp: Vec<[u8; 4]>,
+    /// Items that can be used during the battle; using one removes it.
+    pub bag: Vec<u16>,
+    /// How many times this side has tried to run.
+    #[serde(default)]
+    pub escape_attempts: u32,
+}
+
+impl BattleSide {
+    pub fn new(team: Vec<Monster>) -> Self {
+        match Dex::global() {
+            Ok(dex) => Self::new_in(dex, team),
+            Err(_) => Self::new_in(&Dex::new(), team),
+        }
+    }
+    /// Create a side with full PP on every move, looking moves up in the given dex.
+    /// The first monster that hasn't fainted is sent out.
+    pub fn new_in(dex: &Dex, team: Vec<Monster>) -> Self {
+        let pp = team
+            .iter()
+            .map(|mon| {
+                let mut pp = [0u8; 4];
+                for (i, val) in pp.iter_mut().enumerate() {
+                    *val = mon
+                        .get_move_in(dex, i)
+                        .and_then(|mv| mv.pp)
+                        .unwrap_or_default();
+                }
+                pp
+            })
+            .collect();
+        let active = team.iter().position(|mon| mon.hp > 0).unwrap_or_default();
+        Self {
+            team,
+            active,
+            pp,
+            bag: Vec::new(),
+            escape_attempts: 0,
+        }
+    }
+    pub fn with_bag(mut self, bag: Vec<u16>) -> Self {
+        self.bag = bag;
+        self
+    }
+    pub fn active(&self) -> &Monster {
+        &self.team[self.active]
+    }
+    pub fn active_mut(&mut self) -> &mut Monster {
+        &mut self.team[self.active]
+    }
+    /// Whether every team member has fainted.
+    pub fn is_defeated(&self) -> bool {
+        self.team.iter().all(|mon| mon.hp == 0)
+    }
+    /// Whether the active monster has no move with PP left, and must use Struggle.
+    pub fn must_struggle(&self, dex: &Dex) -> bool {
+        let active = self.active();
+        (0..4)
+            .all(|slot| active.get_move_in(dex, slot).is_none() || self.pp[self.active][slot] == 0)
+    }
+    fn can_switch_to(&self, index: usize) -> bool {
+        index != self.active && self.team.get(index).is_some_and(|mon| mon.hp > 0)
+    }
+}
+
+/// A singles battle between two sides.
+///
+/// Every random roll is drawn from the battle's own `BattleRng`, so a battle started
+/// from the same seed with the same actions always plays out the same way.
+/// Everything that happens is recorded as a `BattleEvent`.
+///
+/// Rules that changed between generations are chosen one at a time and default to the
+/// latest one: critical hits by `crit_mechanic`, the damage formula by `damage_mechanic`,
+/// paralysis's Speed drop by `paralysis_mechanic`, and move categories and priorities
+/// by the dex's `category_mechanic` and `priority_mechanic`.
+#[derive(Debug, Clone)]
+pub struct Battle {
+    pub sides: [BattleSide; 2],
+    /// The number of turns taken so far.
+    pub turn: u32,
+    /// Whether either side may run, as in wild battles.
+    pub can_run: bool,
+    /// The turns left before Trick Room ends, including the current one.
+    pub trick_room: u8,
+    /// The rules deciding the chance and damage of critical hits.
+    pub crit_mechanic: CritMechanic,
+    /// The order the damage formula's modifiers are applied in.
+    pub damage_mechanic: DamageMechanic,
+    /// How much paralysis lowers Speed.
+    pub paralysis_mechanic: ParalysisMechanic,
+    seed: u64,
+    rng: BattleRng,
+    /// The category and priority rules of the dex the first turn was taken with.
+    mechanics: Option<(CategoryMechanic, PriorityMechanic)>,
+    outcome: Option<BattleOutcome>,
+    log: Vec<BattleEvent>,
+    /// The sides as they were before the battle started, for replays.
+    initial: [BattleSide; 2],
+    steps: Vec<ReplayStep>,
+}
+
+impl Battle {
+    /// Start a trainer battle, sending out each side's first monster.
+    /// A side with no monster able to battle loses immediately.
+    pub fn new(first: BattleSide, second: BattleSide, seed: u64) -> Self {
+        let mut battle = Self {
+            initial: [first.clone(), second.clone()],
+            sides: [first, second],
+            turn: 0,
+            can_run: false,
+            trick_room: 0,
+            crit_mechanic: CritMechanic::default(),
+            damage_mechanic: DamageMechanic::default(),
+            paralysis_mechanic: ParalysisMechanic::default(),
+            seed,
+            rng: BattleRng::new(seed),
+            mechanics: None,
+            outcome: None,
+            log: Vec::new(),
+            steps: Vec::new(),
+        };
+        battle.check_outcome();
+        if battle.outcome.is_none() {
+            for side in 0..2 {
+                battle.send_out(side);
+            }
+            for side in 0..2 {
+                battle.switch_in(side);
+            }
+        }
+        battle
+    }
+    /// Start a wild battle, which either side may run from.
+    pub fn wild(first: BattleSide, second: BattleSide, seed: u64) -> Self {
+        Self {
+            can_run: true,
+            ..Self::new(first, second, seed)
+        }
+    }
+    /// The seed the battle's RNG was created from.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+    pub fn outcome(&self) -> Option<BattleOutcome> {
+        self.outcome
+    }
+    /// The side that won, once every monster on the other side has fainted.
+    /// A battle that ended in a draw has no winner.
+    pub fn winner(&self) -> Option<usize> {
+        match self.outcome {
+            Some(BattleOutcome::Winner(side)) => Some(side),
+            _ => None,
+        }
+    }
+    /// Every event since the battle started.
+    pub fn events(&self) -> &[BattleEvent] {
+        &self.log
+    }
+    /// Record this battle so far as a replay.
+    pub fn replay(&self) -> Replay {
+        Replay {
+            version: REPLAY_VERSION,
+            sides: self.initial.clone(),
+            wild: self.can_run,
+            seed: self.seed,
+            rng: self.rng.algorithm(),
+            crit_mechanic: self.crit_mechanic,
+            damage_mechanic: self.damage_mechanic,
+            paralysis_mechanic: self.paralysis_mechanic,
+            category_mechanic: self.mechanics.unwrap_or_default().0,
+            priority_mechanic: self.mechanics.unwrap_or_default().1,
+            steps: self.steps.clone(),
+            events: self.log.clone(),
+        }
+    }
+    /// Whether a side's active monster fainted and must be replaced with `replace`.
+    pub fn needs_switch(&self, side: usize) -> bool {
+        self.outcome.is_none() && self.sides[side].active().hp == 0
+    }
+    /// Send out a replacement for a side's fainted monster between turns,
+    /// returning the resulting events.
+    pub fn replace(&mut self, side: usize, index: usize) -> result::Result<Vec<BattleEvent>> {
+        if self.outcome.is_some() {
+            return Err(BattleError::BattleOver);
+        }
+        if !self.needs_switch(side) {
+            return Err(BattleError::NoSwitchNeeded(side));
+        }
+        if !self.sides[side].can_switch_to(index) {
+            return Err(BattleError::InvalidSwitch(index));
+        }
+        let start = self.log.len();
+        self.steps.push(ReplayStep::Replace { side, index });
+        self.switch(side, index);
+        Ok(self.log[start..].to_vec())
+    }
+    /// Resolve one turn with an action for each side, using the default dex.
+    pub fn take_turn(&mut self, actions: [Action; 2]) -> result::Result<Vec<BattleEvent>> {
+        self.take_turn_in(Dex::global()?, actions)
+    }
+    /// Resolve one turn with an action for each side, looking moves up in the given dex,
+    /// and return the turn's events.
+    /// Both actions are validated before anything happens, so an invalid action
+    /// leaves the battle unchanged.
+    pub fn take_turn_in(
+        &mut self,
+        dex: &Dex,
+        actions: [Action; 2],
+    ) -> result::Result<Vec<BattleEvent>> {
+        if self.outcome.is_some() {
+            return Err(BattleError::BattleOver);
+        }
+        let mechanics = (dex.category_mechanic, dex.priority_mechanic);
+        if self.mechanics.is_some_and(|recorded| recorded != mechanics) {
+            return Err(BattleError::MechanicsChanged);
+        }
+        for (side, action) in actions.iter().enumerate() {
+            self.validate(dex, side, action)?;
+        }
+        self.mechanics = Some(mechanics);
+        let start = self.log.len();
+        self.steps.push(ReplayStep::Turn(actions.clone()));
+        self.turn += 1;
+        self.emit(BattleEvent::TurnStarted { turn: self.turn });
+        let first = self.first_side(dex, &actions);
+        for side in [first, 1 - first] {
+            if self.outcome.is_some() {
+                break;
+            }
+            self.act(dex, side, &actions[side])?;
+            self.check_outcome();
+        }
+        for side in [first, 1 - first] {
+            if self.outcome.is_some() {
+                break;
+            }
+            self.residual(side);
+            self.check_outcome();
+        }
+        if self.outcome.is_none() && self.trick_room > 0 {
+            self.trick_room -= 1;
+            if self.trick_room == 0 {
+                self.emit(BattleEvent::TrickRoomEnded);
+            }
+        }
+        Ok(self.log[start..].to_vec())
+    }
+    fn emit(&mut self, event: BattleEvent) {
+        self.log.push(event);
+    }
+    fn validate(&self, dex: &Dex, side: usize, action: &Action) -> result::Result<()> {
+        if self.needs_switch(side) {
+            return Err(BattleError::MustSwitch(side));
+        }
+        let own = &self.sides[side];
+        match action {
+            Action::Move(_) if own.must_struggle(dex) => {}
+            Action::Move(slot) => {
+                if *slot >= 4 || own.active().get_move_in(dex, *slot).is_none() {
+                    return Err(BattleError::InvalidMove(*slot));
+                }
+                if own.pp[own.active][*slot] == 0 {
+                    return Err(BattleError::NoPp(*slot));
+                }
+            }
+            Action::Switch(index) => {
+                if !own.can_switch_to(*index) {
+                    return Err(BattleError::InvalidSwitch(*index));
+                }
+            }
+            Action::UseItem { item, target } => {
+                if !own.bag.contains(item) {
+                    return Err(BattleError::ItemNotInBag(*item));
+                }
+                let target = own
+                    .team
+                    .get(*target)
+                    .ok_or(BattleError::InvalidTarget(*target))?;
+                if !item_has_effect(*item, target) {
+                    return Err(BattleError::UnusableItem(*item));
+                }
+            }
+            Action::Run => {
+                if !self.can_run {
+                    return Err(BattleError::CannotRun);
+                }
+            }
+        }
+        Ok(())
+    }
+    /// The side whose action resolves first. Moves in a higher priority bracket
+    /// go first, then the faster monster, or the slower one under Trick Room.
+    /// Speed ties are broken randomly.
+    fn first_side(&mut self, dex: &Dex, actions: &[Action; 2]) -> usize {
+        let (first, second) = (actions[0].bracket(), actions[1].bracket());
+        if first != second {
+            return if first < second { 0 } else { 1 };
+        }
+        let priority = |side: usize| match actions[side] {
+            Action::Move(_) if self.sides[side].must_struggle(dex) => 0,
+            Action::Move(slot) => self.sides[side]
+                .active()
+                .get_move_in(dex, slot)
+                .map(|mv| mv.priority)
+                .unwrap_or_default(),
+            _ => 0,
+        };
+        let (first, second) = (priority(0), priority(1));
+        if first != second {
+            return if first > second { 0 } else { 1 };
+        }
+        let (first, second) = (
+            self.sides[0]
+                .active()
+                .effective_speed(self.paralysis_mechanic),
+            self.sides[1]
+                .active()
+                .effective_speed(self.paralysis_mechanic),
+        );
+        if first != second {
+            let faster = if first > second { 0 } else { 1 };
+            return if self.trick_room > 0 {
+                1 - faster
+            } else {
+                faster
+            };
+        }
+        self.rng.below(2) as usize
+    }
+    fn act(&mut self, dex: &Dex, side: usize, action: &Action) -> result::Result<()> {
+        match action {
+            Action::Move(slot) => self.use_move(dex, side, *slot)?,
+            Action::Switch(index) => self.switch(side, *index),
+            Action::UseItem { item, target } => self.use_item(side, *item, *target),
+            Action::Run => {
+                if self.try_escape(side) {
+                    self.emit(BattleEvent::Fled { side });
+                    self.end(BattleOutcome::Fled(side));
+                } else {
+                    self.emit(BattleEvent::EscapeFailed { side });
+                }
+            }
+        }
+        Ok(())
+    }
+    fn use_move(&mut self, dex: &Dex, side: usize, slot: usize) -> result::Result<()> {
+        let foe = 1 - side;
+        // A monster that fainted earlier in the turn doesn't get to act.
+        if self.sides[side].active().hp == 0 || !self.can_act(side) {
+            return Ok(());
+        }
+        let struggling = self.sides[side].must_struggle(dex);
+        let mv = if struggling {
+            dex.get_move(STRUGGLE)?
+        } else {
+            self.sides[side]
+                .active()
+                .get_move_in(dex, slot)
+                .ok_or(BattleError::InvalidMove(slot))?
+        };
+        let own = &mut self.sides[side];
+        let index = own.active;
+        if !struggling {
+            own.pp[index][slot] = own.pp[index][slot].saturating_sub(1);
+        }
+        self.emit(BattleEvent::MoveUsed {
+            side,
+            index,
+            move_id: mv.id,
+        });
+
+        let attacker = self.sides[side].active();
+        let target = self.sides[foe].active();
+        let mut calc = DamageCalc::new(attacker, target, mv, false);
+        if struggling {
+            calc = calc.map(typeless);
+        }
+        let immune = match (&calc, mv.effect()) {
+            (Some(calc), _) => calc.effectiveness == 0f32,
+            (None, Some(MoveEffect::Status(_))) => {
+                let effectiveness = ElementType::effectiveness(&mv.element, &target.elements);
+                effectiveness == 0f32 || target.ability_hooks().is_immune(target, mv, effectiveness)
+            }
+            _ => false,
+        };
+        if immune {
+            self.emit(BattleEvent::Effectiveness {
+                side: foe,
+                multiplier: 0f32,
+            });
+            return Ok(());
+        }
+        if let Some(accuracy) = mv.accuracy {
+            let accuracy = attacker
+                .ability_hooks()
+                .modify_accuracy(attacker, mv, accuracy);
+            let evade = target.combat_mods.get_mod(MonsterAttribute::EV);
+            if !attacker.hits(self.rng.range(1, 100) as u8, accuracy, evade) {
+                self.emit(BattleEvent::MoveMissed { side });
+                return Ok(());
+            }
+        }
+        self.emit(BattleEvent::MoveHit { side });
+        if let Some(mut calc) = calc {
+            let attacker = self.sides[side].active();
+            let chance = self.crit_mechanic.chance(attacker.crit_stage(mv));
+            if self.rng.below(chance) == 0 {
+                let target = self.sides[foe].active();
+                calc = DamageCalc::new(attacker, target, mv, true).unwrap_or(calc);
+                if struggling {
+                    calc = typeless(calc);
+                }
+            }
+            calc.crit_mechanic = self.crit_mechanic;
+            calc.damage_mechanic = self.damage_mechanic;
+            let damage = calc.roll(self.rng.range(85, 100) as u8);
+            if calc.critical {
+                self.emit(BattleEvent::CriticalHit { side: foe });
+            }
+            if calc.effectiveness != 1f32 {
+                self.emit(BattleEvent::Effectiveness {
+                    side: foe,
+                    multiplier: calc.effectiveness,
+                });
+            }
+            self.damage(foe, damage);
+            if mv.makes_contact() {
+                self.on_contact(side, foe);
+            }
+            if struggling {
+                // The Gen4+ recoil: a quarter of the user's max HP.
+                let recoil = (self.sides[side].active().max_hp() / 4).max(1);
+                self.damage(side, recoil);
+            }
+        }
+        if let Some(effect) = mv.effect() {
+            let applied = match effect {
+                MoveEffect::Status(status) => self.inflict(foe, status),
+                MoveEffect::TargetStage(attr, stages) => {
+                    let target = self.sides[foe].active();
+                    let blocked = stages < 0 && target.ability_hooks().blocks_stat_drop(&attr);
+                    !blocked && self.change_stage(foe, attr, stages)
+                }
+                MoveEffect::UserStage(attr, stages) => self.change_stage(side, attr, stages),
+                MoveEffect::TrickRoom => {
+                    if self.trick_room > 0 {
+                        self.trick_room = 0;
+                        self.emit(BattleEvent::TrickRoomEnded);
+                    } else {
+                        self.trick_room = TRICK_ROOM_TURNS;
+                        self.emit(BattleEvent::TrickRoomStarted {
+                            turns: TRICK_ROOM_TURNS,
+                        });
+                    }
+                    true
+                }
+                MoveEffect::FocusEnergy => {
+                    let index = self.sides[side].active;
+                    let user = self.sides[side].active_mut();
+                    let focused = user.combat_status.contains(MonsterStatus::FCS);
+                    if !focused {
+                        user.combat_status.insert(MonsterStatus::FCS);
+                        self.emit(BattleEvent::StatusApplied {
+                            side,
+                            index,
+                            status: MonsterStatus::FCS,
+                        });
+                    }
+                    !focused
+                }
+            };
+            if !applied {
+                self.emit(BattleEvent::MoveFailed { side });
+            }
+        }
+        Ok(())
+    }
+    /// Whether a side's active monster can move this turn, emitting why when it can't.
+    /// A sleeping monster counts down its sleep and wakes up once it reaches 0,
+    /// and a paralyzed monster is fully paralyzed 1 time in 4.
+    fn can_act(&mut self, side: usize) -> bool {
+        let index = self.sides[side].active;
+        let mon = self.sides[side].active_mut();
+        if mon.combat_status.contains(MonsterStatus::SLP) {
+            if mon.status_turns > 0 {
+                mon.status_turns -= 1;
+                self.emit(BattleEvent::StatusPrevented {
+                    side,
+                    index,
+                    status: MonsterStatus::SLP,
+                });
+                return false;
+            }
+            mon.combat_status.remove(MonsterStatus::SLP);
+            self.emit(BattleEvent::StatusCured {
+                side,
+                index,
+                statuses: MonsterStatus::SLP.into(),
+            });
+        }
+        let paralyzed = self.sides[side]
+            .active()
+            .combat_status
+            .contains(MonsterStatus::PAR);
+        if paralyzed && self.rng.below(FULL_PARALYSIS_CHANCE) == 0 {
+            self.emit(BattleEvent::StatusPrevented {
+                side,
+                index,
+                status: MonsterStatus::PAR,
+            });
+            return false;
+        }
+        true
+    }
+    /// Deal end-of-turn status damage to a side's active monster: 1/8 of its max HP
+    /// for burn and poison, and 1/16 more each turn for bad poison.
+    fn residual(&mut self, side: usize) {
+        let mon = self.sides[side].active_mut();
+        if mon.hp == 0 {
+            return;
+        }
+        let max_hp = mon.max_hp() as u32;
+        let amount = if mon.combat_status.contains(MonsterStatus::TOX) {
+            mon.status_turns = (mon.status_turns + 1).min(15);
+            max_hp * mon.status_turns as u32 / 16
+        } else if mon
+            .combat_status
+            .intersects(MonsterStatus::BRN | MonsterStatus::PSN)
+        {
+            max_hp / 8
+        } else {
+            return;
+        };
+        self.damage(side, amount.max(1) as u16);
+    }
+    /// Deal damage to a side's active monster, fainting it at 0 HP.
+    fn damage(&mut self, side: usize, amount: u16) {
+        let index = self.sides[side].active;
+        let target = self.sides[side].active_mut();
+        let hp_before = target.hp;
+        target.hp = target.hp.saturating_sub(amount);
+        let hp_after = target.hp;
+        self.emit(BattleEvent::Damage {
+            side,
+            index,
+            amount,
+            hp_before,
+            hp_after,
+        });
+        if hp_after == 0 {
+            self.sides[side].active_mut().in_combat = false;
+            self.emit(BattleEvent::Fainted { side, index });
+        }
+    }
+    /// Trigger the contact effect of the defender's ability on the attacker.
+    fn on_contact(&mut self, attacker: usize, defender: usize) {
+        let holder = self.sides[defender].active();
+        if let Some(ContactEffect::AttackerStatus(status, chance)) =
+            holder.ability_hooks().on_contact(holder)
+        {
+            if self.rng.below(100) < chance as u32 {
+                self.inflict(attacker, status);
+            }
+        }
+    }
+    /// Inflict a major status on a side's active monster.
+    /// Returns false when the monster can't get the status.
+    fn inflict(&mut self, side: usize, status: MonsterStatus) -> bool {
+        let index = self.sides[side].active;
+        let target = self.sides[side].active_mut();
+        if !can_inflict(status, target) {
+            return false;
+        }
+        target.combat_status.insert(status);
+        target.status_turns = match status {
+            MonsterStatus::SLP => self.rng.range(1, MAX_SLEEP_TURNS as u32) as u8,
+            _ => 0,
+        };
+        self.emit(BattleEvent::StatusApplied {
+            side,
+            index,
+            status,
+        });
+        true
+    }
+    /// Change a stat stage of a side's active monster.
+    /// Returns false when the stage is already at its limit.
+    fn change_stage(&mut self, side: usize, attr: MonsterAttribute, stages: i8) -> bool {
+        let index = self.sides[side].active;
+        let mods = &mut self.sides[side].active_mut().combat_mods;
+        let before = mods.get(&attr);
+        mods.apply_stages(attr.clone(), stages);
+        let stage = mods.get(&attr);
+        if stage == before {
+            return false;
+        }
+        self.emit(BattleEvent::StatStageChanged {
+            side,
+            index,
+            stat: attr,
+            change: stage - before,
+            stage,
+        });
+        true
+    }
+    fn use_item(&mut self, side: usize, item: u16, index: usize) {
+        let own = &mut self.sides[side];
+        if let Some(pos) = own.bag.iter().position(|id| *id == item) {
+            own.bag.remove(pos);
+        }
+        let target = &mut own.team[index];
+        let hp_before = target.hp;
+        let statuses = target.combat_status;
+        use_item(item, target);
+        let hp_after = target.hp;
+        let cured = statuses & !target.combat_status;
+        self.emit(BattleEvent::ItemUsed {
+            side,
+            item,
+            target: index,
+        });
+        if hp_after != hp_before {
+            self.emit(BattleEvent::Healed {
+                side,
+                index,
+                hp_before,
+                hp_after,
+            });
+        }
+        if !cured.is_empty() {
+            self.emit(BattleEvent::StatusCured {
+                side,
+                index,
+                statuses: cured,
+            });
+        }
+    }
+    fn switch(&mut self, side: usize, index: usize) {
+        let outgoing = self.sides[side].active_mut();
+        outgoing.in_combat = false;
+        outgoing.combat_mods = MonsterStages::new();
+        // Volatile statuses like Focus Energy end when the monster switches out.
+        outgoing.combat_status &= major_statuses();
+        // The bad poison counter starts over after switching out.
+        if outgoing.combat_status.contains(MonsterStatus::TOX) {
+            outgoing.status_turns = 0;
+        }
+        self.sides[side].active = index;
+        self.send_out(side);
+        self.switch_in(side);
+    }
+    fn send_out(&mut self, side: usize) {
+        let own = &mut self.sides[side];
+        let index = own.active;
+        let mon = own.active_mut();
+        mon.in_combat = true;
+        let species = mon.pid;
+        self.emit(BattleEvent::SwitchedIn {
+            side,
+            index,
+            species,
+        });
+    }
+    /// Apply the switch-in effects of a side's active monster's ability.
+    fn switch_in(&mut self, side: usize) {
+        let foe = 1 - side;
+        let holder = self.sides[side].active();
+        if holder.hp == 0 || self.sides[foe].active().hp == 0 {
+            return;
+        }
+        let effects = holder.ability_hooks().on_switch_in(holder);
+        for effect in effects.iter() {
+            let SwitchInEffect::FoeStatStage(attr, _) = effect;
+            let before = self.sides[foe].active().combat_mods.get(attr);
+            if !effect.apply(self.sides[foe].active_mut()) {
+                continue;
+            }
+            let stage = self.sides[foe].active().combat_mods.get(attr);
+            if stage != before {
+                self.emit(BattleEvent::StatStageChanged {
+                    side: foe,
+                    index: self.sides[foe].active,
+                    stat: attr.clone(),
+                    change: stage - before,
+                    stage,
+                });
+            }
+        }
+    }
+    /// Roll an escape attempt with the Gen3 formula. A monster at least as fast
+    /// as its foe always escapes, and each failed attempt raises the odds.
+    fn try_escape(&mut self, side: usize) -> bool {
+        self.sides[side].escape_attempts += 1;
+        let speed = self.sides[side].active().get_stat(MonsterAttribute::SPD) as u32;
+        let foe_speed = self.sides[1 - side]
+            .active()
+            .get_stat(MonsterAttribute::SPD) as u32;
+        if speed >= foe_speed {
+            return true;
+        }
+        let attempts = self.sides[side].escape_attempts;
+        let odds = (speed * 128 / foe_speed.max(1) + 30 * attempts) % 256;
+        self.rng.below(256) < odds
+    }
+    /// End the battle once a side is defeated, or in a draw when both are.
+    fn check_outcome(&mut self) {
+        if self.outcome.is_some() {
+            return;
+        }
+        let outcome = match [self.sides[0].is_defeated(), self.sides[1].is_defeated()] {
+            [true, true] => BattleOutcome::Draw,
+            [true, false] => BattleOutcome::Winner(1),
+            [false, true] => BattleOutcome::Winner(0),
+            [false, false] => return,
+        };
+        self.end(outcome);
+    }
+    fn end(&mut self, outcome: BattleOutcome) {
+        self.outcome = Some(outcome);
+        self.emit(BattleEvent::BattleEnded { outcome });
+    }
+}
+
+/// Struggle has no type from Gen4 on: it hits every monster for neutral damage.
+fn typeless(calc: DamageCalc) -> DamageCalc {
+    DamageCalc {
+        stab: false,
+        effectiveness: 1f32,
+        ..calc
+    }
+}
+
+/// The major statuses, of which a monster can only have one.
+fn major_statuses() -> BitFlags<MonsterStatus> {
+    MonsterStatus::BRN
+        | MonsterStatus::FRZ
+        | MonsterStatus::PAR
+        | MonsterStatus::PSN
+        | MonsterStatus::SLP
+        | MonsterStatus::TOX
+}
+
+/// Whether `status` can be inflicted on `target`.
+/// Monsters with a major status can't get another, Fire types can't be burned,
+/// Poison and Steel types can't be poisoned, and abilities like Limber block their status.
+fn can_inflict(status: MonsterStatus, target: &Monster) -> bool {
+    if target.combat_status.intersects(major_statuses())
+        || target.ability_hooks().blocks_status(status)
+    {
+        return false;
+    }
+    let has_type =
+        |element: ElementType| target.elements.0 == element || target.elements.1 == element;
+    match status {
+        MonsterStatus::BRN => !has_type(ElementType::Fire),
+        MonsterStatus::PSN | MonsterStatus::TOX => {
+            !has_type(ElementType::Poison) && !has_type(ElementType::Steel)
+        }
+        _ => true,
+    }
+}
+
+/// The statuses an item cures.
+fn item_cures(item: u16) -> BitFlags<MonsterStatus> {
+    match item {
+        ANTIDOTE => MonsterStatus::PSN | MonsterStatus::TOX,
+        BURN_HEAL => MonsterStatus::BRN.into(),
+        ICE_HEAL => MonsterStatus::FRZ.into(),
+        AWAKENING => MonsterStatus::SLP.into(),
+        PARALYZE_HEAL => MonsterStatus::PAR.into(),
+        FULL_HEAL | FULL_RESTORE => major_statuses() | MonsterStatus::CON,
+        _ => BitFlags::empty(),
+    }
+}
+
+/// The HP an item restores, capped at the target's max HP later.
+fn item_heals(item: u16, max_hp: u16) -> u16 {
+    match item {
+        POTION => 20,
+        SUPER_POTION => 50,
+        HYPER_POTION => 200,
+        MAX_POTION | FULL_RESTORE => max_hp,
+        _ => 0,
+    }
+}
+
+/// Whether using `item` on `target` would do anything.
+fn item_has_effect(item: u16, target: &Monster) -> bool {
+    match item {
+        REVIVE | MAX_REVIVE => target.hp == 0,
+        _ if target.hp == 0 => false,
+        _ => {
+            let heals = item_heals(item, target.max_hp()) > 0 && target.hp < target.max_hp();
+            heals || target.combat_status.intersects(item_cures(item))
+        }
+    }
+}
+
+fn use_item(item: u16, target: &mut Monster) {
+    let max_hp = target.max_hp();
+    match item {
+        REVIVE => target.hp = (max_hp / 2).max(1),
+        MAX_REVIVE => target.hp = max_hp,
+        _ => {
+            target.hp = target
+                .hp
+                .saturating_add(item_heals(item, max_hp))
+                .min(max_hp);
+            target.combat_status.remove(item_cures(item));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        error::BattleError, Action, Battle, BattleOutcome, BattleSide, POTION, REVIVE, STRUGGLE,
+        SUPER_POTION,
+    };
+    use crate::game::{
+        abilities,
+        events::BattleEvent,
+        monster::{Monster, MonsterAttribute, MonsterStatus, ParalysisMechanic},
+        moves::PokemonMove,
+    };
+
+    fn monster(species: usize, level: u8) -> Monster {
+        let mut mon = Monster::from_dex(species);
+        mon.set_level(level);
+        mon.moves = (33, 0, 0, 0);
+        mon
+    }
+
+    /// Play a battle to the end with every monster using its first move.
+    fn play(battle: &mut Battle) {
+        while battle.outcome().is_none() {
+            for side in 0..2 {
+                if battle.needs_switch(side) {
+                    battle.replace(side, 1).unwrap();
+                }
+            }
+            battle
+                .take_turn([Action::Move(0), Action::Move(0)])
+                .unwrap();
+            assert!(battle.turn < 200);
+        }
+    }
+
+    #[test]
+    fn battle_to_the_end() {
+        let first = BattleSide::new(vec![monster(1, 20)]);
+        let second = BattleSide::new(vec![monster(4, 20), monster(7, 5)]);
+        let mut battle = Battle::new(first, second, 42);
+        let mut rematch = battle.clone();
+        play(&mut battle);
+        let winner = battle.winner().unwrap();
+        assert!(battle.sides[1 - winner].is_defeated());
+        assert!(battle.sides[winner].pp[0][0] < 35);
+        assert_eq!(
+            battle.take_turn([Action::Move(0), Action::Move(0)]),
+            Err(BattleError::BattleOver)
+        );
+
+        // The same seed plays out the same battle.
+        play(&mut rematch);
+        assert_eq!(rematch.turn, battle.turn);
+        assert_eq!(rematch.sides, battle.sides);
+    }
+
+    #[test]
+    fn invalid_actions() {
+        let mut first = monster(1, 20);
+        first.hp -= 10;
+        let first = BattleSide::new(vec![first, monster(25, 20)]).with_bag(vec![POTION]);
+        let second = BattleSide::new(vec![monster(4, 20)]);
+        let mut battle = Battle::new(first, second, 1);
+        let attempt = |action: Action| {
+            battle
+                .clone()
+                .take_turn([action, Action::Move(0)])
+                .unwrap_err()
+        };
+        assert_eq!(attempt(Action::Move(1)), BattleError::InvalidMove(1));
+        assert_eq!(attempt(Action::Switch(0)), BattleError::InvalidSwitch(0));
+        assert_eq!(attempt(Action::Switch(2)), BattleError::InvalidSwitch(2));
+        assert_eq!(
+            attempt(Action::UseItem {
+                item: SUPER_POTION,
+                target: 0
+            }),
+            BattleError::ItemNotInBag(SUPER_POTION)
+        );
+        assert_eq!(
+            attempt(Action::UseItem {
+                item: POTION,
+                target: 1
+            }),
+            BattleError::UnusableItem(POTION)
+        );
+        assert_eq!(attempt(Action::Run), BattleError::CannotRun);
+
+        battle.sides[0].team[0].moves = (33, 45, 0, 0);
+        battle.sides[0].pp[0] = [0, 40, 0, 0];
+        assert_eq!(
+            battle.take_turn([Action::Move(0), Action::Move(0)]),
+            Err(BattleError::NoPp(0))
+        );
+        assert_eq!(battle.turn, 0);
+    }
+
+    #[test]
+    fn struggle() {
+        // One monster each and no PP left: Struggle keeps the battle going to the end.
+        let mut first = BattleSide::new(vec![monster(1, 20)]);
+        first.pp[0] = [0; 4];
+        let mut battle = Battle::new(first, BattleSide::new(vec![monster(92, 20)]), 4);
+        let events = battle
+            .take_turn([Action::Move(0), Action::Move(0)])
+            .unwrap();
+        let max_hp = battle.sides[0].active().max_hp();
+        assert!(events.contains(&BattleEvent::MoveUsed {
+            side: 0,
+            index: 0,
+            move_id: STRUGGLE,
+        }));
+        // Struggle is typeless, so it hits Gastly, and the user takes recoil.
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, BattleEvent::Damage { side: 1, .. })));
+        assert!(events.iter().any(|event| matches!(
+            event,
+            BattleEvent::Damage { side: 0, amount, .. } if *amount == max_hp / 4
+        )));
+        assert_eq!(battle.sides[0].pp[0], [0; 4]);
+        while battle.outcome().is_none() {
+            battle
+                .take_turn([Action::Move(3), Action::Move(0)])
+                .unwrap();
+            assert!(battle.turn < 100);
+        }
+    }
+
+    #[test]
+    fn switches_and_items() {
+        let mut gyarados = monster(130, 30);
+        gyarados.ability = Some(abilities::INTIMIDATE);
+        let mut fainted = monster(1, 30);
+        fainted.hp = 0;
+        let first = BattleSide::new(vec![monster(25, 30), gyarados, fainted])
+            .with_bag(vec![POTION, REVIVE]);
+        let second = BattleSide::new(vec![monster(4, 30)]);
+        let mut battle = Battle::new(first, second, 7);
+
+        let events = battle
+            .take_turn([Action::Switch(1), Action::Move(0)])
+            .unwrap();
+        assert_eq!(
+            events[1..3],
+            [
+                BattleEvent::SwitchedIn {
+                    side: 0,
+                    index: 1,
+                    species: 130
+                },
+                BattleEvent::StatStageChanged {
+                    side: 1,
+                    index: 0,
+                    stat: MonsterAttribute::ATT,
+                    change: -1,
+                    stage: -1
+                },
+            ]
+        );
+        assert_eq!(battle.sides[0].active, 1);
+        assert!(!battle.sides[0].team[0].in_combat);
+        // Intimidate lowered the foe's Attack before it attacked.
+        let foe = battle.sides[1].active();
+        assert_eq!(foe.combat_mods.internal[&MonsterAttribute::ATT], -1);
+
+        battle
+            .take_turn([
+                Action::UseItem {
+                    item: REVIVE,
+                    target: 2,
+                },
+                Action::Move(0),
+            ])
+            .unwrap();
+        let revived = &battle.sides[0].team[2];
+        assert_eq!(revived.hp, revived.max_hp() / 2);
+        assert_eq!(battle.sides[0].bag, vec![POTION]);
+
+        battle.sides[0].team[0]
+            .combat_status
+            .insert(MonsterStatus::PAR);
+        assert_eq!(
+            battle
+                .clone()
+                .take_turn([
+                    Action::UseItem {
+                        item: POTION,
+                        target: 0,
+                    },
+                    Action::Move(0),
+                ])
+                .unwrap_err(),
+            BattleError::UnusableItem(POTION)
+        );
+    }
+
+    #[test]
+    fn event_stream() {
+        let mut pikachu = monster(25, 30);
+        pikachu.moves = (86, 45, 33, 0);
+        let first = BattleSide::new(vec![pikachu]);
+        let second = BattleSide::new(vec![monster(4, 30), monster(74, 30)]);
+        let mut battle = Battle::new(first, second, 11);
+        assert_eq!(battle.events().len(), 2);
+
+        // Thunder Wave paralyzes, then fails on a paralyzed target.
+        let events = battle
+            .take_turn([Action::Move(0), Action::Move(0)])
+            .unwrap();
+        assert_eq!(events[0], BattleEvent::TurnStarted { turn: 1 });
+        assert!(events.contains(&BattleEvent::MoveUsed {
+            side: 0,
+            index: 0,
+            move_id: 86
+        }));
+        assert!(events.contains(&BattleEvent::StatusApplied {
+            side: 1,
+            index: 0,
+            status: MonsterStatus::PAR
+        }));
+        let events = battle
+            .take_turn([Action::Move(0), Action::Move(0)])
+            .unwrap();
+        assert!(events.contains(&BattleEvent::MoveFailed { side: 0 }));
+
+        let events = battle
+            .take_turn([Action::Move(1), Action::Move(0)])
+            .unwrap();
+        assert!(events.contains(&BattleEvent::StatStageChanged {
+            side: 1,
+            index: 0,
+            stat: MonsterAttribute::ATT,
+            change: -1,
+            stage: -1
+        }));
+        for event in events.iter() {
+            if let BattleEvent::Damage {
+                amount,
+                hp_before,
+                hp_after,
+                ..
+            } = event
+            {
+                assert_eq!(*hp_after, hp_before.saturating_sub(*amount));
+            }
+        }
+
+        // Ground types are immune to Thunder Wave.
+        battle.sides[1].team[0].hp = 0;
+        battle.replace(1, 1).unwrap();
+        let events = battle
+            .take_turn([Action::Move(0), Action::Move(0)])
+            .unwrap();
+        assert!(events.contains(&BattleEvent::Effectiveness {
+            side: 1,
+            multiplier: 0.0
+        }));
+
+        let json = serde_json::to_string(battle.events()).unwrap();
+        let parsed: Vec<BattleEvent> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, battle.events());
+    }
+
+    /// The side that moved first in a turn's events.
+    fn first_mover(events: &[BattleEvent]) -> usize {
+        events
+            .iter()
+            .find_map(|event| match event {
+                BattleEvent::MoveUsed { side, .. } => Some(*side),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn turn_order() {
+        let first_moves = [Action::Move(0), Action::Move(0)];
+        let mut slow = monster(1, 5);
+        slow.moves = (33, 98, 0, 0);
+        let mut battle = Battle::new(
+            BattleSide::new(vec![slow]),
+            BattleSide::new(vec![monster(25, 50)]),
+            0,
+        );
+        let events = battle.clone().take_turn(first_moves.clone()).unwrap();
+        assert_eq!(first_mover(&events), 1);
+        // Quick Attack goes first regardless of Speed.
+        let events = battle
+            .take_turn([Action::Move(1), Action::Move(0)])
+            .unwrap();
+        assert_eq!(first_mover(&events), 0);
+
+        // Paralysis halves Speed, or quarters it before Gen7, and stat stages apply.
+        let (gen3, gen7) = (ParalysisMechanic::Gen3To6, ParalysisMechanic::Gen7Plus);
+        let mut pikachu = monster(25, 50);
+        let bulbasaur = monster(1, 50);
+        let speed = pikachu.get_stat(MonsterAttribute::SPD);
+        assert!(pikachu.effective_speed(gen7) > bulbasaur.effective_speed(gen7));
+        pikachu.combat_status.insert(MonsterStatus::PAR);
+        assert_eq!(pikachu.effective_speed(gen7), speed / 2);
+        assert_eq!(pikachu.effective_speed(gen3), speed / 4);
+        assert!(pikachu.effective_speed(gen3) < bulbasaur.effective_speed(gen3));
+        pikachu.combat_status.remove(MonsterStatus::PAR);
+        pikachu.combat_mods.apply_stages(MonsterAttribute::SPD, -6);
+        assert!(pikachu.effective_speed(gen7) < bulbasaur.effective_speed(gen7));
+
+        // Trick Room goes last, then lets the slower monster move first for five turns.
+        let mut bulbasaur = bulbasaur;
+        bulbasaur.moves = (45, 433, 0, 0);
+        let mut pikachu = monster(25, 50);
+        pikachu.moves = (45, 0, 0, 0);
+        let mut battle = Battle::new(
+            BattleSide::new(vec![bulbasaur]),
+            BattleSide::new(vec![pikachu]),
+            0,
+        );
+        let events = battle
+            .take_turn([Action::Move(1), Action::Move(0)])
+            .unwrap();
+        assert_eq!(first_mover(&events), 1);
+        assert!(events.contains(&BattleEvent::TrickRoomStarted { turns: 5 }));
+        for _ in 0..4 {
+            let events = battle.take_turn(first_moves.clone()).unwrap();
+            assert_eq!(first_mover(&events), 0);
+        }
+        assert_eq!(battle.events().last(), Some(&BattleEvent::TrickRoomEnded));
+        let events = battle.take_turn(first_moves.clone()).unwrap();
+        assert_eq!(first_mover(&events), 1);
+
+        // Speed ties are broken by the battle RNG.
+        let twin = monster(25, 50);
+        let firsts: Vec<usize> = (0..16)
+            .map(|seed| {
+                let mut battle = Battle::new(
+                    BattleSide::new(vec![twin.clone()]),
+                    BattleSide::new(vec![twin.clone()]),
+                    seed,
+                );
+                first_mover(&battle.take_turn(first_moves.clone()).unwrap())
+            })
+            .collect();
+        assert!(firsts.contains(&0) && firsts.contains(&1));
+    }
+
+    #[test]
+    fn focus_energy() {
+        let mut focused = monster(1, 30);
+        focused.moves = (116, 45, 0, 0);
+        let first = BattleSide::new(vec![focused, monster(4, 30)]);
+        let mut second = monster(7, 30);
+        second.moves = (45, 0, 0, 0);
+        let mut battle = Battle::new(first, BattleSide::new(vec![second]), 3);
+        let events = battle
+            .take_turn([Action::Move(0), Action::Move(0)])
+            .unwrap();
+        assert!(events.contains(&BattleEvent::StatusApplied {
+            side: 0,
+            index: 0,
+            status: MonsterStatus::FCS,
+        }));
+        let user = battle.sides[0].active();
+        let tackle = PokemonMove::get_move(33).unwrap();
+        assert_eq!(user.crit_stage(&tackle), 2);
+        // Focus Energy can't stack.
+        let events = battle
+            .take_turn([Action::Move(0), Action::Move(0)])
+            .unwrap();
+        assert!(events.contains(&BattleEvent::MoveFailed { side: 0 }));
+        // Switching out ends it.
+        battle
+            .take_turn([Action::Switch(1), Action::Move(0)])
+            .unwrap();
+        assert!(!battle.sides[0].team[0]
+            .combat_status
+            .contains(MonsterStatus::FCS));
+    }
+
+    #[test]
+    fn running() {
+        let fast = BattleSide::new(vec![monster(25, 50)]);
+        let slow = BattleSide::new(vec![monster(1, 5)]);
+        let mut battle = Battle::wild(fast, slow, 3);
+        battle.take_turn([Action::Run, Action::Move(0)]).unwrap();
+        assert_eq!(battle.outcome(), Some(BattleOutcome::Fled(0)));
+        assert_eq!(battle.sides[0].escape_attempts, 1);
+        assert_eq!(battle.sides[1].escape_attempts, 0);
+        assert_eq!(battle.winner(), None);
+        // Fleeing happens before the foe can attack.
+        assert_eq!(
+            battle.sides[0].active().hp,
+            battle.sides[0].active().max_hp()
+        );
+    }
+
+    #[test]
+    fn double_ko() {
+        // Struggle faints the foe, then its recoil faints the user.
+        let mut first = BattleSide::new(vec![monster(25, 50)]);
+        first.pp[0] = [0; 4];
+        first.active_mut().hp = 1;
+        let mut second = BattleSide::new(vec![monster(1, 5)]);
+        second.active_mut().hp = 1;
+        let mut battle = Battle::new(first, second, 2);
+        let events = battle
+            .take_turn([Action::Move(0), Action::Move(0)])
+            .unwrap();
+        assert!(events.contains(&BattleEvent::Fainted { side: 0, index: 0 }));
+        assert!(events.contains(&BattleEvent::Fainted { side: 1, index: 0 }));
+        assert_eq!(battle.outcome(), Some(BattleOutcome::Draw));
+        assert_eq!(battle.winner(), None);
+        assert_eq!(
+            events.last(),
+            Some(&BattleEvent::BattleEnded {
+                outcome: BattleOutcome::Draw
+            })
+        );
+    }
+
+    #[test]
+    fn status_effects() {
+        let mut sleeper = monster(4, 30);
+        sleeper.moves = (45, 0, 0, 0);
+        let mut poisoned = monster(7, 30);
+        poisoned.moves = (45, 0, 0, 0);
+        let mut battle = Battle::new(
+            BattleSide::new(vec![sleeper]),
+            BattleSide::new(vec![poisoned]),
+            5,
+        );
+        assert!(battle.inflict(0, MonsterStatus::SLP));
+        assert!(battle.inflict(1, MonsterStatus::TOX));
+        let max_hp = battle.sides[1].active().max_hp();
+
+        // The sleeper skips each turn it sleeps for, then wakes up and moves.
+        let asleep = battle.sides[0].active().status_turns as usize;
+        assert!((1..=3).contains(&asleep));
+        let mut turns = Vec::new();
+        for _ in 0..=asleep {
+            turns.push(
+                battle
+                    .take_turn([Action::Move(0), Action::Move(0)])
+                    .unwrap(),
+            );
+        }
+        let skipped = BattleEvent::StatusPrevented {
+            side: 0,
+            index: 0,
+            status: MonsterStatus::SLP,
+        };
+        assert!(turns[..asleep]
+            .iter()
+            .all(|events| events.contains(&skipped)));
+        let woke = &turns[asleep];
+        assert!(woke.contains(&BattleEvent::StatusCured {
+            side: 0,
+            index: 0,
+            statuses: MonsterStatus::SLP.into(),
+        }));
+        assert!(woke.contains(&BattleEvent::MoveUsed {
+            side: 0,
+            index: 0,
+            move_id: 45,
+        }));
+
+        // Bad poison deals 1/16 more of the max HP each turn.
+        let lost: u16 = (1..=turns.len() as u16).map(|n| max_hp * n / 16).sum();
+        assert_eq!(battle.sides[1].active().hp, max_hp - lost);
+
+        // Plain poison deals a flat 1/8.
+        let mut battle = Battle::new(
+            BattleSide::new(vec![monster(4, 30)]),
+            BattleSide::new(vec![monster(7, 30)]),
+            5,
+        );
+        battle.sides[1].active_mut().moves = (45, 0, 0, 0);
+        battle.sides[0].active_mut().moves = (45, 0, 0, 0);
+        assert!(battle.inflict(1, MonsterStatus::PSN));
+        let max_hp = battle.sides[1].active().max_hp();
+        for _ in 0..2 {
+            battle
+                .take_turn([Action::Move(0), Action::Move(0)])
+                .unwrap();
+        }
+        assert_eq!(battle.sides[1].active().hp, max_hp - max_hp / 8 * 2);
+    }
+
+    #[test]
+    fn ability_statuses() {
+        // Limber blocks paralysis.
+        let mut zapper = monster(25, 30);
+        zapper.moves = (86, 0, 0, 0);
+        let mut persian = monster(53, 30);
+        persian.ability = Some(abilities::LIMBER);
+        let mut battle = Battle::new(
+            BattleSide::new(vec![zapper]),
+            BattleSide::new(vec![persian]),
+            5,
+        );
+        let events = battle
+            .take_turn([Action::Move(0), Action::Move(0)])
+            .unwrap();
+        assert!(events.contains(&BattleEvent::MoveFailed { side: 0 }));
+        assert!(!battle.inflict(1, MonsterStatus::PAR));
+        assert!(battle.inflict(1, MonsterStatus::BRN));
+
+        // Static may paralyze a monster that touches it, but not one that doesn't.
+        let tackle = PokemonMove::get_move(33).unwrap();
+        let ember = PokemonMove::get_move(52).unwrap();
+        let earthquake = PokemonMove::get_move(89).unwrap();
+        assert!(tackle.makes_contact());
+        assert!(!ember.makes_contact());
+        assert!(!earthquake.makes_contact());
+        let mut snorlax = monster(143, 50);
+        snorlax.ability = Some(abilities::STATIC);
+        snorlax.moves = (45, 0, 0, 0);
+        let mut charmander = monster(4, 5);
+        charmander.moves = (33, 52, 0, 0);
+        let mut battle = Battle::new(
+            BattleSide::new(vec![snorlax]),
+            BattleSide::new(vec![charmander]),
+            5,
+        );
+        for _ in 0..10 {
+            battle
+                .take_turn([Action::Move(0), Action::Move(1)])
+                .unwrap();
+        }
+        assert!(battle.sides[1].active().combat_status.is_empty());
+        for _ in 0..20 {
+            battle
+                .take_turn([Action::Move(0), Action::Move(0)])
+                .unwrap();
+        }
+        assert!(battle.sides[1]
+            .active()
+            .combat_status
+            .contains(MonsterStatus::PAR));
+    }
+}
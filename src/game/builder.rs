@@ -0,0 +1,423 @@
+use super::{
+    dex::Dex,
+    experience::MAX_LEVEL,
+    monster::{
+        Monster, MonsterAttribute, MonsterGender, MonsterNature, MonsterStats, MAX_EV,
+        MAX_EV_TOTAL, MAX_IV,
+    },
+    personality::{OriginalTrainer, Personality, PersonalityCriteria},
+    species::GenderRatio,
+};
+use rand::prelude::*;
+
+/// The longest nickname a monster can have, in characters.
+pub const MAX_NICKNAME_LEN: usize = 12;
+
+pub mod error {
+    use crate::game::{data::error::DataError, monster::MonsterAttribute};
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum BuildError {
+        /// The species, a move, the item or the ability is missing from the data.
+        Data(DataError),
+        InvalidLevel(u8),
+        InvalidIv(MonsterAttribute, u8),
+        /// An EV is above 252.
+        InvalidEv(MonsterAttribute, u8),
+        /// The EVs add up to more than 510.
+        InvalidEvTotal(u16),
+        /// The species can't have this gender.
+        InvalidGender,
+        /// The species can't know this move at the requested level.
+        IllegalMove(u16),
+        DuplicateMove(u16),
+        /// The species can't have this ability.
+        IllegalAbility(u16),
+        InvalidNickname(String),
+        /// The personality value doesn't produce the requested traits.
+        PersonalityMismatch(u32),
+    }
+
+    impl std::fmt::Display for BuildError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            let msg = match self {
+                BuildError::Data(e) => e.to_string(),
+                BuildError::InvalidLevel(level) => format!("Invalid level: {}.", level),
+                BuildError::InvalidIv(attr, value) => {
+                    format!("Invalid {:?} IV: {}.", attr, value)
+                }
+                BuildError::InvalidEv(attr, value) => {
+                    format!("Invalid {:?} EV: {}.", attr, value)
+                }
+                BuildError::InvalidEvTotal(total) => {
+                    format!("EVs total {}, more than the maximum of 510.", total)
+                }
+                BuildError::InvalidGender => "Invalid gender for this species.".to_string(),
+                BuildError::IllegalMove(id) => format!("Move {} can't be learned.", id),
+                BuildError::DuplicateMove(id) => format!("Move {} is known twice.", id),
+                BuildError::IllegalAbility(id) => {
+                    format!("Ability {} is not available to this species.", id)
+                }
+                BuildError::InvalidNickname(name) => format!("Invalid nickname: {:?}.", name),
+                BuildError::PersonalityMismatch(pid) => {
+                    format!(
+                        "Personality value {:08X} doesn't match the requested traits.",
+                        pid
+                    )
+                }
+            };
+            write!(f, "{}", msg)
+        }
+    }
+
+    impl std::error::Error for BuildError {}
+
+    impl From<DataError> for BuildError {
+        fn from(e: DataError) -> Self {
+            BuildError::Data(e)
+        }
+    }
+}
+
+pub mod result {
+    pub type Result<T> = std::result::Result<T, super::error::BuildError>;
+}
+
+use error::BuildError;
+
+/// Builds a specific monster, validating every value against the species data.
+/// Values that aren't set are generated like `Monster::from_dex`, with a personality
+/// value generated to match the requested nature, gender, ability and shininess.
+#[derive(Debug, Clone, Default)]
+pub struct MonsterBuilder {
+    species: usize,
+    level: Option<u8>,
+    nature: Option<MonsterNature>,
+    gender: Option<MonsterGender>,
+    iv: Option<MonsterStats>,
+    ev: Option<MonsterStats>,
+    moves: Option<(u16, u16, u16, u16)>,
+    held_item: Option<u16>,
+    nickname: Option<String>,
+    ability: Option<u16>,
+    personality: Option<Personality>,
+    trainer: Option<OriginalTrainer>,
+    shiny: Option<bool>,
+}
+
+impl MonsterBuilder {
+    pub fn new(species: usize) -> Self {
+        Self {
+            species,
+            ..Self::default()
+        }
+    }
+    pub fn level(mut self, level: u8) -> Self {
+        self.level = Some(level);
+        self
+    }
+    pub fn nature(mut self, nature: MonsterNature) -> Self {
+        self.nature = Some(nature);
+        self
+    }
+    pub fn gender(mut self, gender: MonsterGender) -> Self {
+        self.gender = Some(gender);
+        self
+    }
+    pub fn ivs(mut self, iv: MonsterStats) -> Self {
+        self.iv = Some(iv);
+        self
+    }
+    pub fn evs(mut self, ev: MonsterStats) -> Self {
+        self.ev = Some(ev);
+        self
+    }
+    /// Set the moveset, with 0 for empty slots.
+    /// Defaults to the species' level-up moves for the level.
+    pub fn moves(mut self, moves: (u16, u16, u16, u16)) -> Self {
+        self.moves = Some(moves);
+        self
+    }
+    pub fn held_item(mut self, item: u16) -> Self {
+        self.held_item = Some(item);
+        self
+    }
+    pub fn nickname<S: Into<String>>(mut self, nickname: S) -> Self {
+        self.nickname = Some(nickname.into());
+        self
+    }
+    pub fn ability(mut self, ability: u16) -> Self {
+        self.ability = Some(ability);
+        self
+    }
+    /// Use this personality value instead of generating one matching the other traits.
+    pub fn personality(mut self, personality: Personality) -> Self {
+        self.personality = Some(personality);
+        self
+    }
+    pub fn trainer(mut self, trainer: OriginalTrainer) -> Self {
+        self.trainer = Some(trainer);
+        self
+    }
+    pub fn shiny(mut self, shiny: bool) -> Self {
+        self.shiny = Some(shiny);
+        self
+    }
+    /// Build the monster using the default dex.
+    pub fn build(&self) -> result::Result<Monster> {
+        self.build_in(Dex::global()?)
+    }
+    /// Build the monster using the given dex.
+    pub fn build_in(&self, dex: &Dex) -> result::Result<Monster> {
+        self.build_with(dex, &mut thread_rng())
+    }
+    /// Build the monster using the given dex, generating unset values from `rng`.
+    pub fn build_with<R: Rng + ?Sized>(&self, dex: &Dex, rng: &mut R) -> result::Result<Monster> {
+        let data = dex.get_species(self.species)?;
+        let mut mon = Monster::try_from_dex_with(dex, self.species, rng)?;
+
+        let level = self.level.unwrap_or(mon.level);
+        if !(1..=MAX_LEVEL).contains(&level) {
+            return Err(BuildError::InvalidLevel(level));
+        }
+        mon.set_level_in(dex, level);
+
+        let ratio = data
+            .info
+            .as_ref()
+            .map(|info| info.gender_ratio)
+            .unwrap_or_default();
+        if let Some(gender) = &self.gender {
+            let valid = match (ratio, gender) {
+                (GenderRatio::Genderless, MonsterGender::None) => true,
+                (GenderRatio::Genderless, _) | (_, MonsterGender::None) => false,
+                (GenderRatio::Female(0), MonsterGender::Female) => false,
+                (GenderRatio::Female(8), MonsterGender::Male) => false,
+                _ => true,
+            };
+            if !valid {
+                return Err(BuildError::InvalidGender);
+            }
+        }
+        if let Some(iv) = &self.iv {
+            for i in 0..6u8 {
+                let attr = MonsterAttribute::from(i);
+                let value = iv.get(attr.clone());
+                if value > MAX_IV {
+                    return Err(BuildError::InvalidIv(attr, value));
+                }
+            }
+            mon.iv = iv.clone();
+        }
+        if let Some(ev) = &self.ev {
+            for i in 0..6u8 {
+                let attr = MonsterAttribute::from(i);
+                let value = ev.get(attr.clone());
+                if value > MAX_EV {
+                    return Err(BuildError::InvalidEv(attr, value));
+                }
+            }
+            let total = ev.total();
+            if total > MAX_EV_TOTAL {
+                return Err(BuildError::InvalidEvTotal(total));
+            }
+            mon.ev = ev.clone();
+        }
+
+        mon.moves = match self.moves {
+            Some(moves) => {
+                let ids = [moves.0, moves.1, moves.2, moves.3];
+                for (i, id) in ids.iter().enumerate().filter(|(_, id)| **id != 0) {
+                    dex.get_move(*id as usize)?;
+                    if ids[..i].contains(id) {
+                        return Err(BuildError::DuplicateMove(*id));
+                    }
+                    // Species without learnset data may know any move.
+                    if !data.learnset.level_up.is_empty() && !data.learnset.can_learn(*id, level) {
+                        return Err(BuildError::IllegalMove(*id));
+                    }
+                }
+                moves
+            }
+            None if !data.learnset.level_up.is_empty() => data.learnset.default_moves(level),
+            None => mon.moves,
+        };
+
+        if let Some(item) = self.held_item {
+            dex.get_item(item as usize)?;
+            mon.held_item = Some(item);
+        }
+        let slots = &data.abilities;
+        if let Some(ability) = self.ability {
+            dex.get_ability(ability as usize)?;
+            if !slots.regular.is_empty() && !slots.contains(ability) {
+                return Err(BuildError::IllegalAbility(ability));
+            }
+        }
+
+        // Nature, gender, ability slot and shininess all come from the personality value.
+        if let Some(trainer) = self.trainer {
+            mon.trainer = trainer;
+        }
+        let criteria = PersonalityCriteria {
+            nature: self.nature.clone(),
+            gender: self.gender.clone(),
+            gender_ratio: ratio,
+            ability_slot: self
+                .ability
+                .filter(|_| slots.regular.len() > 1)
+                .and_then(|ability| slots.regular.iter().position(|id| *id == ability)),
+            shiny: self.shiny,
+            unown_form: None,
+        };
+        let personality = match self.personality {
+            Some(pid) if criteria.matches(pid, &mon.trainer) => pid,
+            Some(pid) => return Err(BuildError::PersonalityMismatch(pid.0)),
+            None => criteria
+                .generate(rng, &mon.trainer)
+                .ok_or(BuildError::InvalidGender)?,
+        };
+        mon.ability = None;
+        mon.set_personality_in(dex, personality);
+        if self.ability.is_some() {
+            // Hidden abilities aren't tied to the personality value.
+            mon.ability = self.ability;
+        }
+        if let Some(nickname) = &self.nickname {
+            let len = nickname.chars().count();
+            if nickname.trim().is_empty() || len > MAX_NICKNAME_LEN {
+                return Err(BuildError::InvalidNickname(nickname.clone()));
+            }
+            mon.nickname = Some(nickname.clone());
+        }
+
+        mon.recompute_stats();
+        mon.hp = mon.max_hp();
+        Ok(mon)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{error::BuildError, MonsterBuilder};
+    use crate::game::{
+        abilities,
+        data::error::DataError,
+        monster::{MonsterAttribute, MonsterGender, MonsterNature, MonsterStats},
+        personality::{OriginalTrainer, Personality},
+    };
+
+    #[test]
+    fn build() {
+        let pikachu = MonsterBuilder::new(25)
+            .level(50)
+            .nature(MonsterNature::Timid)
+            .gender(MonsterGender::Female)
+            .ivs(MonsterStats { internal: [31; 6] })
+            .evs(MonsterStats {
+                internal: [4, 0, 0, 252, 252, 0],
+            })
+            .moves((84, 86, 0, 0))
+            .held_item(234)
+            .nickname("Sparky")
+            .ability(abilities::STATIC)
+            .build()
+            .unwrap();
+        assert_eq!(pikachu.level, 50);
+        assert_eq!(pikachu.nature, MonsterNature::Timid);
+        assert_eq!(pikachu.gender, MonsterGender::Female);
+        assert_eq!(pikachu.ev.get(MonsterAttribute::SPD), 252);
+        assert_eq!(pikachu.moves, (84, 86, 0, 0));
+        assert_eq!(pikachu.held_item, Some(234));
+        assert_eq!(pikachu.nickname.as_deref(), Some("Sparky"));
+        assert_eq!(pikachu.hp, pikachu.max_hp());
+        assert_eq!(pikachu.max_hp(), 111);
+        assert_eq!(pikachu.personality.nature(), MonsterNature::Timid);
+
+        let trainer = OriginalTrainer::new(1, 2);
+        let mawile = MonsterBuilder::new(303)
+            .trainer(trainer)
+            .shiny(true)
+            .gender(MonsterGender::Male)
+            .ability(abilities::INTIMIDATE)
+            .build()
+            .unwrap();
+        assert!(mawile.is_shiny());
+        assert_eq!(mawile.trainer, trainer);
+        assert_eq!(mawile.gender, MonsterGender::Male);
+        assert_eq!(mawile.ability, Some(abilities::INTIMIDATE));
+        assert_eq!(mawile.personality.ability_slot(), 1);
+
+        // Unset moves follow the level.
+        let charmander = MonsterBuilder::new(4).level(13).build().unwrap();
+        assert_eq!(charmander.moves, (10, 45, 52, 232));
+        assert_eq!(charmander.hp, charmander.max_hp());
+    }
+
+    #[test]
+    fn validation() {
+        let err = |builder: MonsterBuilder| builder.build().unwrap_err();
+        assert_eq!(
+            err(MonsterBuilder::new(9999)),
+            BuildError::Data(DataError::UnknownSpecies(9999))
+        );
+        assert_eq!(
+            err(MonsterBuilder::new(25).level(101)),
+            BuildError::InvalidLevel(101)
+        );
+        assert_eq!(
+            err(MonsterBuilder::new(25).ivs(MonsterStats {
+                internal: [31, 32, 31, 31, 31, 31]
+            })),
+            BuildError::InvalidIv(MonsterAttribute::ATT, 32)
+        );
+        assert_eq!(
+            err(MonsterBuilder::new(25).evs(MonsterStats {
+                internal: [252, 252, 252, 0, 0, 0]
+            })),
+            BuildError::InvalidEvTotal(756)
+        );
+        assert_eq!(
+            err(MonsterBuilder::new(25).evs(MonsterStats {
+                internal: [255, 0, 0, 0, 0, 0]
+            })),
+            BuildError::InvalidEv(MonsterAttribute::HP, 255)
+        );
+        assert_eq!(
+            err(MonsterBuilder::new(113).gender(MonsterGender::Male)),
+            BuildError::InvalidGender
+        );
+        assert_eq!(
+            err(MonsterBuilder::new(292).gender(MonsterGender::Female)),
+            BuildError::InvalidGender
+        );
+        // Charmander can't know Ember at level 5, nor the same move twice.
+        assert_eq!(
+            err(MonsterBuilder::new(4).level(5).moves((10, 52, 0, 0))),
+            BuildError::IllegalMove(52)
+        );
+        assert_eq!(
+            err(MonsterBuilder::new(4).moves((10, 10, 0, 0))),
+            BuildError::DuplicateMove(10)
+        );
+        assert_eq!(
+            err(MonsterBuilder::new(25).ability(abilities::INTIMIDATE)),
+            BuildError::IllegalAbility(abilities::INTIMIDATE)
+        );
+        assert_eq!(
+            err(MonsterBuilder::new(25).held_item(60000)),
+            BuildError::Data(DataError::UnknownItem(60000))
+        );
+        assert!(matches!(
+            err(MonsterBuilder::new(25).nickname("A very long nickname")),
+            BuildError::InvalidNickname(_)
+        ));
+        // Personality value 0 is a Hardy nature.
+        assert_eq!(
+            err(MonsterBuilder::new(25)
+                .nature(MonsterNature::Timid)
+                .personality(Personality(0))),
+            BuildError::PersonalityMismatch(0)
+        );
+    }
+}
@@ -0,0 +1,145 @@
+use super::{
+    monster::{Monster, MonsterStatus},
+    moves::PokemonMove,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+pub const SCOPE_LENS: u16 = 232;
+pub const RAZOR_CLAW: u16 = 326;
+
+/// The critical hit stages added by Focus Energy.
+pub const FOCUS_ENERGY_STAGES: u8 = 2;
+
+/// The rules deciding the chance and damage of a critical hit.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Eq, Hash, Default)]
+pub enum CritMechanic {
+    /// Gen3 to Gen5: critical hits deal double damage.
+    Gen3To5,
+    /// Gen6: critical hits deal 1.5x damage and become more likely at high stages.
+    Gen6,
+    /// Gen7+: as Gen6, but a stage 0 critical hit is rarer.
+    #[default]
+    Gen7Plus,
+}
+
+impl CritMechanic {
+    /// The chance of a critical hit at `stage`, as 1 in this many.
+    pub fn chance(&self, stage: u8) -> u32 {
+        let table: &[u32] = match self {
+            CritMechanic::Gen3To5 => &[16, 8, 4, 3, 2],
+            CritMechanic::Gen6 => &[16, 8, 2, 1],
+            CritMechanic::Gen7Plus => &[24, 8, 2, 1],
+        };
+        table[(stage as usize).min(table.len() - 1)]
+    }
+    /// The damage multiplier of a critical hit, as a fraction.
+    pub fn multiplier(&self) -> (u32, u32) {
+        match self {
+            CritMechanic::Gen3To5 => (2, 1),
+            CritMechanic::Gen6 | CritMechanic::Gen7Plus => (3, 2),
+        }
+    }
+    /// Roll for a critical hit at `stage`.
+    pub fn roll<R: Rng + ?Sized>(&self, rng: &mut R, stage: u8) -> bool {
+        rng.gen_range(0..self.chance(stage)) == 0
+    }
+}
+
+impl PokemonMove {
+    /// The critical hit stage of this move: 1 for high critical hit ratio moves.
+    pub fn crit_stage(&self) -> u8 {
+        match self.id {
+            // Karate Chop, Razor Wind, Razor Leaf, Sky Attack, Crabhammer, Slash,
+            // Aeroblast, Cross Chop, Blaze Kick, Air Cutter, Poison Tail, Leaf Blade,
+            // Night Slash, Shadow Claw, Psycho Cut, Cross Poison, Stone Edge,
+            // Attack Order, Spacial Rend
+            2 | 13 | 75 | 143 | 152 | 163 | 177 | 238 | 299 | 314 | 342 | 348 | 400 | 421 | 427
+            | 440 | 444 | 454 | 460 => 1,
+            _ => 0,
+        }
+    }
+}
+
+impl Monster {
+    /// The critical hit stage of `mv` used by this monster, from the move,
+    /// Focus Energy, a held Scope Lens or Razor Claw and the monster's ability.
+    pub fn crit_stage(&self, mv: &PokemonMove) -> u8 {
+        let mut stage = mv.crit_stage();
+        if self.combat_status.contains(MonsterStatus::FCS) {
+            stage += FOCUS_ENERGY_STAGES;
+        }
+        if matches!(self.held_item, Some(SCOPE_LENS) | Some(RAZOR_CLAW)) {
+            stage += 1;
+        }
+        stage + self.ability_hooks().crit_stage_bonus()
+    }
+    /// Roll for a critical hit with `mv`.
+    pub fn roll_crit<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        mv: &PokemonMove,
+        mechanic: CritMechanic,
+    ) -> bool {
+        mechanic.roll(rng, self.crit_stage(mv))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CritMechanic, SCOPE_LENS};
+    use crate::game::{
+        abilities::SUPER_LUCK,
+        damage::DamageCalc,
+        monster::{Monster, MonsterAttribute, MonsterStatus},
+        moves::PokemonMove,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn crit_stages() {
+        let tackle = PokemonMove::get_move(33).unwrap();
+        let slash = PokemonMove::get_move(163).unwrap();
+        assert_eq!(slash.crit_stage(), 1);
+        let mut mon = Monster::from_dex(4);
+        assert_eq!(mon.crit_stage(&tackle), 0);
+        mon.combat_status.insert(MonsterStatus::FCS);
+        mon.held_item = Some(SCOPE_LENS);
+        mon.ability = Some(SUPER_LUCK);
+        assert_eq!(mon.crit_stage(&slash), 5);
+
+        assert_eq!(CritMechanic::Gen3To5.chance(0), 16);
+        assert_eq!(CritMechanic::Gen3To5.chance(5), 2);
+        assert_eq!(CritMechanic::Gen6.chance(2), 2);
+        assert_eq!(CritMechanic::Gen7Plus.chance(0), 24);
+        assert_eq!(CritMechanic::Gen7Plus.chance(3), 1);
+        let mut rng = StdRng::seed_from_u64(0);
+        assert!((0..20).all(|_| mon.roll_crit(&mut rng, &slash, CritMechanic::Gen7Plus)));
+    }
+
+    #[test]
+    fn crit_damage() {
+        let mut attacker = Monster::from_dex(4);
+        attacker.set_level(50);
+        let mut defender = Monster::from_dex(1);
+        defender.set_level(50);
+        let tackle = PokemonMove::get_move(33).unwrap();
+        let plain = DamageCalc::new(&attacker, &defender, &tackle, false).unwrap();
+
+        // Crits ignore the attacker's drops and the defender's boosts, but not the reverse.
+        attacker.combat_mods.apply_stages(MonsterAttribute::ATT, -2);
+        defender.combat_mods.apply_stages(MonsterAttribute::DEF, 2);
+        let crit = DamageCalc::new(&attacker, &defender, &tackle, true).unwrap();
+        assert_eq!((crit.attack, crit.defense), (plain.attack, plain.defense));
+        attacker.combat_mods.apply_stages(MonsterAttribute::ATT, 4);
+        defender.combat_mods.apply_stages(MonsterAttribute::DEF, -4);
+        let boosted = DamageCalc::new(&attacker, &defender, &tackle, false).unwrap();
+        let crit = DamageCalc::new(&attacker, &defender, &tackle, true).unwrap();
+        assert_eq!(
+            (crit.attack, crit.defense),
+            (boosted.attack, boosted.defense)
+        );
+        assert!(crit.attack > plain.attack && crit.defense < plain.defense);
+    }
+}
@@ -0,0 +1,335 @@
+use super::{
+    critical::CritMechanic,
+    monster::{Monster, MonsterAttribute, MonsterStatus},
+    moves::{MoveCategory, PokemonMove},
+    ElementType,
+};
+use serde::{Deserialize, Serialize};
+
+/// The order and rounding of the damage formula's modifiers. Every step truncates.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Eq, Hash, Default)]
+pub enum DamageMechanic {
+    /// Gen3: burn halves the base damage, then critical hit, STAB, type effectiveness
+    /// and the random roll last. Physical moves deal at least 1 damage before the +2.
+    Gen3,
+    /// Gen4: the base damage divides by 50 before Defense, burn halves it, then critical
+    /// hit, random roll, STAB and type effectiveness.
+    Gen4,
+    /// Gen5+: critical hit, random roll, STAB, type effectiveness and burn, with STAB
+    /// and burn as 4096-based modifiers.
+    #[default]
+    Gen5Plus,
+}
+
+/// The resolved inputs of the damage formula.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DamageCalc {
+    /// The attacker's level.
+    pub level: u8,
+    /// The move's base power.
+    pub power: u16,
+    /// The attacker's Attack or Sp. Attack, after stat stages and abilities.
+    pub attack: u16,
+    /// The defender's Defense or Sp. Defense, after stat stages and abilities.
+    pub defense: u16,
+    /// Whether the move is a critical hit.
+    pub critical: bool,
+    /// The rules deciding a critical hit's damage multiplier.
+    pub crit_mechanic: CritMechanic,
+    /// The order the modifiers are applied in.
+    pub damage_mechanic: DamageMechanic,
+    /// Whether the move is physical.
+    pub physical: bool,
+    /// Whether the move shares an element with the attacker.
+    pub stab: bool,
+    /// The type effectiveness multiplier, one of 0, 0.25, 0.5, 1, 2 or 4.
+    /// 0 when an ability makes the defender immune.
+    pub effectiveness: f32,
+    /// Whether the attacker is burned and using a physical move.
+    pub burned: bool,
+}
+
+/// Modify a value by a 4096-based multiplier, rounding half down like the games.
+fn apply_modifier(value: u32, modifier: u32) -> u32 {
+    (value * modifier + 2047) / 4096
+}
+
+impl DamageCalc {
+    /// Resolve the damage inputs for `attacker` using `mv` against `defender`.
+    /// A critical hit ignores the attacker's lowered and the defender's raised stat stages.
+    /// Returns `None` for Status moves and moves without a base power.
+    pub fn new(
+        attacker: &Monster,
+        defender: &Monster,
+        mv: &PokemonMove,
+        critical: bool,
+    ) -> Option<Self> {
+        if mv.category == MoveCategory::Status {
+            return None;
+        }
+        let power = mv.power? as u16;
+        let (att_attr, def_attr) = match mv.category {
+            MoveCategory::Physical => (MonsterAttribute::ATT, MonsterAttribute::DEF),
+            _ => (MonsterAttribute::SpATT, MonsterAttribute::SpDEF),
+        };
+        let (attack, defense) = if critical {
+            (
+                attacker.get_critical_stat(att_attr, true),
+                defender.get_critical_stat(def_attr, false),
+            )
+        } else {
+            (
+                attacker.get_battle_stat(att_attr),
+                defender.get_battle_stat(def_attr),
+            )
+        };
+        let stab = attacker.elements.0 == mv.element || attacker.elements.1 == mv.element;
+        let physical = mv.category == MoveCategory::Physical;
+        let burned = physical && attacker.combat_status.contains(MonsterStatus::BRN);
+        let defender_hooks = defender.ability_hooks();
+        let mut effectiveness = ElementType::effectiveness(&mv.element, &defender.elements);
+        if defender_hooks.is_immune(defender, mv, effectiveness) {
+            effectiveness = 0f32;
+        }
+        let mut calc = Self {
+            level: attacker.level,
+            power,
+            attack,
+            defense,
+            critical,
+            crit_mechanic: CritMechanic::default(),
+            damage_mechanic: DamageMechanic::default(),
+            physical,
+            stab,
+            effectiveness,
+            burned,
+        };
+        attacker.ability_hooks().on_attack(attacker, mv, &mut calc);
+        defender_hooks.on_defend(defender, mv, &mut calc);
+        calc.defense = calc.defense.max(1);
+        Some(calc)
+    }
+    /// The base damage of the formula, including the +2.
+    /// Under Gen3 and Gen4 rules this already includes burn.
+    pub fn base_damage(&self) -> u32 {
+        let level = (2 * self.level as u32) / 5 + 2;
+        let power = level * self.power as u32 * self.attack as u32;
+        let mut damage = match self.damage_mechanic {
+            DamageMechanic::Gen4 => power / 50 / self.defense as u32,
+            DamageMechanic::Gen3 | DamageMechanic::Gen5Plus => power / self.defense as u32 / 50,
+        };
+        if self.damage_mechanic != DamageMechanic::Gen5Plus && self.burned {
+            damage /= 2;
+        }
+        if self.damage_mechanic == DamageMechanic::Gen3 && self.physical {
+            damage = damage.max(1);
+        }
+        damage + 2
+    }
+    /// Calculate the final damage for a random roll in the range 85..=100.
+    pub fn roll(&self, roll: u8) -> u16 {
+        let mut damage = self.base_damage();
+        if self.critical {
+            let (num, den) = self.crit_mechanic.multiplier();
+            damage = damage * num / den;
+        }
+        let effectiveness = (self.effectiveness * 4f32) as u32;
+        match self.damage_mechanic {
+            DamageMechanic::Gen3 => {
+                if self.stab {
+                    damage = damage * 15 / 10;
+                }
+                damage = damage * effectiveness / 4;
+                damage = damage * roll as u32 / 100;
+            }
+            DamageMechanic::Gen4 => {
+                damage = damage * roll as u32 / 100;
+                if self.stab {
+                    damage = damage * 15 / 10;
+                }
+                damage = damage * effectiveness / 4;
+            }
+            DamageMechanic::Gen5Plus => {
+                damage = damage * roll as u32 / 100;
+                if self.stab {
+                    damage = apply_modifier(damage, 6144);
+                }
+                damage = damage * effectiveness / 4;
+                if self.burned {
+                    damage = apply_modifier(damage, 2048);
+                }
+            }
+        }
+        if damage == 0 && self.effectiveness > 0f32 {
+            damage = 1;
+        }
+        damage.min(u16::MAX as u32) as u16
+    }
+    /// Calculate the damage for all 16 random rolls, from lowest to highest.
+    pub fn range(&self) -> [u16; 16] {
+        let mut res = [0u16; 16];
+        for (i, val) in res.iter_mut().enumerate() {
+            *val = self.roll(85 + i as u8);
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DamageCalc, DamageMechanic};
+    use crate::game::{
+        critical::CritMechanic,
+        monster::{Monster, MonsterStatus},
+        moves::PokemonMove,
+    };
+
+    #[test]
+    fn test_reference_damage() {
+        // Level 75 Glaceon using Ice Fang on Garchomp.
+        let calc = DamageCalc {
+            level: 75,
+            power: 65,
+            attack: 123,
+            defense: 163,
+            critical: false,
+            crit_mechanic: CritMechanic::Gen7Plus,
+            damage_mechanic: DamageMechanic::Gen5Plus,
+            physical: true,
+            stab: true,
+            effectiveness: 4.0,
+            burned: false,
+        };
+        assert_eq!(calc.base_damage(), 33);
+        assert_eq!(
+            calc.range(),
+            [168, 168, 168, 172, 172, 172, 180, 180, 180, 184, 184, 184, 192, 192, 192, 196]
+        );
+        let burned = DamageCalc {
+            burned: true,
+            ..calc.clone()
+        };
+        assert_eq!(burned.roll(100), 98);
+        let critical = DamageCalc {
+            critical: true,
+            ..calc.clone()
+        };
+        assert_eq!(critical.roll(100), 292);
+        let gen3_critical = DamageCalc {
+            crit_mechanic: CritMechanic::Gen3To5,
+            ..critical
+        };
+        assert_eq!(gen3_critical.roll(100), 396);
+        let immune = DamageCalc {
+            effectiveness: 0.0,
+            ..calc
+        };
+        assert_eq!(immune.roll(100), 0);
+    }
+
+    #[test]
+    fn test_gen3_gen4_damage() {
+        // Level 50 Swampert (178 Attack) using Earthquake on a 90 Defense Blaziken.
+        // Base damage: 178 * 100 * 22 / 90 / 50 + 2 = 89.
+        let calc = DamageCalc {
+            level: 50,
+            power: 100,
+            attack: 178,
+            defense: 90,
+            critical: false,
+            crit_mechanic: CritMechanic::Gen3To5,
+            damage_mechanic: DamageMechanic::Gen3,
+            physical: true,
+            stab: true,
+            effectiveness: 2.0,
+            burned: false,
+        };
+        assert_eq!(calc.base_damage(), 89);
+        // 89 * 1.5 = 133, * 2 = 266, then the roll: 266 * 85 / 100 = 226.
+        assert_eq!(calc.roll(85), 226);
+        assert_eq!(calc.roll(100), 266);
+        assert_eq!(
+            calc.range(),
+            [226, 228, 231, 234, 236, 239, 242, 244, 247, 250, 252, 255, 258, 260, 263, 266]
+        );
+        // Gen5+ rolls before STAB: 89 * 85 / 100 = 75, * 1.5 = 112, * 2 = 224.
+        let gen5 = DamageCalc {
+            damage_mechanic: DamageMechanic::Gen5Plus,
+            ..calc.clone()
+        };
+        assert_eq!(gen5.roll(85), 224);
+        // Burn halves the base damage: 4351 / 50 / 2 + 2 = 45, * 1.5 = 67, * 2 = 134.
+        let burned = DamageCalc {
+            burned: true,
+            ..calc.clone()
+        };
+        assert_eq!(burned.base_damage(), 45);
+        assert_eq!(burned.roll(100), 134);
+        assert_eq!(
+            DamageCalc {
+                damage_mechanic: DamageMechanic::Gen5Plus,
+                ..burned.clone()
+            }
+            .roll(100),
+            133
+        );
+        // A critical hit doubles the base damage: 178 * 1.5 = 267, * 2 = 534, * 0.85 = 453.
+        let critical = DamageCalc {
+            critical: true,
+            ..calc.clone()
+        };
+        assert_eq!(critical.roll(85), 453);
+        // Gen4 divides by 50 first: 391600 / 50 / 90 + 2 = 89, then rolls before STAB.
+        let gen4 = DamageCalc {
+            damage_mechanic: DamageMechanic::Gen4,
+            ..calc.clone()
+        };
+        assert_eq!(gen4.base_damage(), 89);
+        assert_eq!(gen4.roll(85), 224);
+        assert_eq!(gen4.roll(100), 266);
+        // Gen3 physical moves deal at least 1 damage before the +2; special moves don't.
+        let weak = DamageCalc {
+            level: 1,
+            power: 10,
+            attack: 5,
+            defense: 200,
+            stab: false,
+            effectiveness: 1.0,
+            ..calc
+        };
+        assert_eq!(weak.base_damage(), 3);
+        assert_eq!(
+            DamageCalc {
+                physical: false,
+                ..weak.clone()
+            }
+            .base_damage(),
+            2
+        );
+        assert_eq!(weak.roll(85), 2);
+    }
+
+    #[test]
+    fn test_monster_damage() {
+        let mut attacker = Monster::from_dex(4);
+        attacker.set_level(50);
+        let mut defender = Monster::from_dex(1);
+        defender.set_level(50);
+        let ember = PokemonMove::get_move(52).unwrap();
+        let calc = DamageCalc::new(&attacker, &defender, &ember, false).unwrap();
+        assert!(calc.stab);
+        assert_eq!(calc.effectiveness, 2.0);
+        let plain = calc.roll(100);
+        attacker.combat_status.insert(MonsterStatus::BRN);
+        // Burn only halves physical moves.
+        assert_eq!(
+            DamageCalc::new(&attacker, &defender, &ember, false)
+                .unwrap()
+                .roll(100),
+            plain
+        );
+        let growl = PokemonMove::get_move(45).unwrap();
+        assert!(DamageCalc::new(&attacker, &defender, &growl, false).is_none());
+    }
+}
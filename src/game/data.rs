@@ -1,4 +1,193 @@
-use serde::Deserialize;
+use serde::{de::DeserializeOwned, Deserialize};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub mod error {
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum DataError {
+        /// A data file could not be read.
+        Io {
+            path: String,
+            message: String,
+        },
+        /// A data file could not be parsed.
+        Parse {
+            path: String,
+            message: String,
+        },
+        UnknownSpecies(usize),
+        UnknownMove(usize),
+        UnknownItem(usize),
+        UnknownAbility(usize),
+        /// A species does not evolve into the requested species.
+        UnknownEvolution {
+            from: usize,
+            to: usize,
+        },
+        /// An overlay defines an ID that is already defined by another overlay,
+        /// or defines the same ID twice.
+        OverlayConflict {
+            source: String,
+            id: usize,
+            existing: String,
+        },
+    }
+
+    impl std::fmt::Display for DataError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            let msg = match self {
+                DataError::Io { path, message } => format!("Unable to read {}: {}", path, message),
+                DataError::Parse { path, message } => {
+                    format!("Unable to parse {}: {}", path, message)
+                }
+                DataError::UnknownSpecies(id) => format!("Unknown species ID: {}.", id),
+                DataError::UnknownMove(id) => format!("Unknown move ID: {}.", id),
+                DataError::UnknownItem(id) => format!("Unknown item ID: {}.", id),
+                DataError::UnknownAbility(id) => format!("Unknown ability ID: {}.", id),
+                DataError::UnknownEvolution { from, to } => {
+                    format!("Species {} does not evolve into {}.", from, to)
+                }
+                DataError::OverlayConflict {
+                    source,
+                    id,
+                    existing,
+                } => format!(
+                    "Overlay {} redefines ID {}, already defined by {}.",
+                    source, id, existing
+                ),
+            };
+            write!(f, "{}", msg)
+        }
+    }
+
+    impl std::error::Error for DataError {}
+
+    impl DataError {
+        /// A parse error for an invalid value in an entry, before its file is known.
+        pub fn invalid(message: String) -> Self {
+            DataError::Parse {
+                path: String::new(),
+                message,
+            }
+        }
+        /// Attach the file to a parse error raised while converting its entries.
+        pub fn in_file(self, name: &str) -> Self {
+            match self {
+                DataError::Parse { path, message } if path.is_empty() => DataError::Parse {
+                    path: name.to_string(),
+                    message,
+                },
+                e => e,
+            }
+        }
+    }
+}
+
+pub mod result {
+    pub type Result<T> = std::result::Result<T, super::error::DataError>;
+}
+
+/// Environment variable overriding the directory data files are loaded from.
+pub const DATA_DIR_VAR: &str = "POKESIM_DATA_DIR";
+
+/// Resolve the directory data files are loaded from.
+/// Uses `POKESIM_DATA_DIR` when set, then `data` in the working directory,
+/// then the `data` directory this crate was built from.
+pub fn data_dir() -> PathBuf {
+    if let Some(dir) = env::var_os(DATA_DIR_VAR) {
+        return PathBuf::from(dir);
+    }
+    let local = PathBuf::from("data");
+    if local.is_dir() {
+        local
+    } else {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("data")
+    }
+}
+
+/// A data file shipped with this crate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DataFile {
+    Pokedex,
+    Moves,
+    Items,
+    Types,
+    Learnsets,
+    Evolutions,
+    Abilities,
+    SpeciesAbilities,
+    SpeciesInfo,
+}
+
+impl DataFile {
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            DataFile::Pokedex => "pokedex.json",
+            DataFile::Moves => "moves.json",
+            DataFile::Items => "items.json",
+            DataFile::Types => "types.json",
+            DataFile::Learnsets => "learnsets.json",
+            DataFile::Evolutions => "evolutions.json",
+            DataFile::Abilities => "abilities.json",
+            DataFile::SpeciesAbilities => "species_abilities.json",
+            DataFile::SpeciesInfo => "species_info.json",
+        }
+    }
+    /// The copy of this file compiled into the crate.
+    #[cfg(feature = "embedded-data")]
+    pub fn embedded(&self) -> &'static [u8] {
+        match self {
+            DataFile::Pokedex => include_bytes!("../../data/pokedex.json"),
+            DataFile::Moves => include_bytes!("../../data/moves.json"),
+            DataFile::Items => include_bytes!("../../data/items.json"),
+            DataFile::Types => include_bytes!("../../data/types.json"),
+            DataFile::Learnsets => include_bytes!("../../data/learnsets.json"),
+            DataFile::Evolutions => include_bytes!("../../data/evolutions.json"),
+            DataFile::Abilities => include_bytes!("../../data/abilities.json"),
+            DataFile::SpeciesAbilities => include_bytes!("../../data/species_abilities.json"),
+            DataFile::SpeciesInfo => include_bytes!("../../data/species_info.json"),
+        }
+    }
+}
+
+/// Load one of the crate's data files.
+/// With the `embedded-data` feature the compiled-in copy is used,
+/// unless `POKESIM_DATA_DIR` is set to load a different dataset at runtime.
+pub fn load_data<T: DeserializeOwned>(file: DataFile) -> result::Result<Vec<T>> {
+    #[cfg(feature = "embedded-data")]
+    if env::var_os(DATA_DIR_VAR).is_none() {
+        return parse_json(file.file_name(), file.embedded());
+    }
+    load_json(data_dir().join(file.file_name()))
+}
+
+/// Read and parse a JSON array of data entries.
+pub fn load_json<T: DeserializeOwned, P: AsRef<Path>>(path: P) -> result::Result<Vec<T>> {
+    let path = path.as_ref();
+    let bytes = fs::read(path).map_err(|e| error::DataError::Io {
+        path: path.display().to_string(),
+        message: e.to_string(),
+    })?;
+    parse_json(&path.display().to_string(), &bytes[..])
+}
+
+/// Read and parse a JSON array of data entries, treating a missing file as empty.
+pub fn load_optional_json<T: DeserializeOwned, P: AsRef<Path>>(path: P) -> result::Result<Vec<T>> {
+    if path.as_ref().exists() {
+        load_json(path)
+    } else {
+        Ok(Vec::new())
+    }
+}
+
+/// Parse a JSON array of data entries. `name` is only used for error reporting.
+pub fn parse_json<T: DeserializeOwned>(name: &str, bytes: &[u8]) -> result::Result<Vec<T>> {
+    serde_json::from_slice(bytes).map_err(|e| error::DataError::Parse {
+        path: name.to_string(),
+        message: e.to_string(),
+    })
+}
 
 /// Entry representing the JSON data for Types.
 #[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize)]
@@ -19,6 +208,7 @@ pub struct ItemsEntry {
 #[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize)]
 pub struct MovesEntry {
     pub accuracy: Option<u8>,
+    pub category: String,
     #[serde(alias = "type")]
     pub _type: String,
     pub cname: String,
@@ -27,6 +217,9 @@ pub struct MovesEntry {
     pub id: u64,
     pub power: Option<u8>,
     pub pp: Option<u8>,
+    /// The move's priority bracket. Moves without one use `PokemonMove::default_priority`.
+    #[serde(default)]
+    pub priority: Option<i8>,
 }
 
 /// Entry representing a Pokedex entry's name object.
@@ -64,3 +257,81 @@ pub struct PokedexEntry {
     pub _type: Vec<String>,
     pub base: PokedexBaseEntry,
 }
+
+/// Entry representing a move learned by level up.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize)]
+pub struct LevelUpMoveEntry {
+    pub level: u8,
+    #[serde(alias = "move")]
+    pub _move: u64,
+}
+
+/// Entry representing the JSON data for a species' learnset.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize)]
+pub struct LearnsetEntry {
+    pub id: u64,
+    #[serde(default)]
+    pub level_up: Vec<LevelUpMoveEntry>,
+    #[serde(default)]
+    pub tm: Vec<u64>,
+    #[serde(default)]
+    pub egg: Vec<u64>,
+    #[serde(default)]
+    pub tutor: Vec<u64>,
+}
+
+/// Entry representing the JSON data for an evolution.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize)]
+pub struct EvolutionEntry {
+    pub from: u64,
+    pub to: u64,
+    /// One of "level", "item", "trade", "friendship", "held_item", "known_move" or "other".
+    pub trigger: String,
+    pub level: Option<u8>,
+    pub item: Option<u64>,
+    /// "day" or "night".
+    pub time: Option<String>,
+    /// "male" or "female".
+    pub gender: Option<String>,
+    /// The move that must be known, for "known_move".
+    pub known_move: Option<u64>,
+    /// A description of the requirement, for "other".
+    pub condition: Option<String>,
+}
+
+/// Entry representing the JSON data for Abilities.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize)]
+pub struct AbilitiesEntry {
+    pub id: u64,
+    pub name: TypesEntry,
+}
+
+/// Entry representing the JSON data for a species' abilities.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize)]
+pub struct SpeciesAbilitiesEntry {
+    pub id: u64,
+    /// The one or two regular abilities.
+    #[serde(default)]
+    pub abilities: Vec<u64>,
+    pub hidden: Option<u64>,
+}
+
+/// Entry representing the JSON data for a species' metadata.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize)]
+pub struct SpeciesInfoEntry {
+    pub id: u64,
+    /// The chance of being female in eighths, or -1 for genderless species.
+    pub gender_rate: i8,
+    pub catch_rate: u8,
+    pub base_exp: u16,
+    pub ev_yield: PokedexBaseEntry,
+    /// One of "erratic", "fast", "medium-fast", "medium-slow", "slow" or "fluctuating".
+    pub growth_rate: String,
+    pub egg_groups: Vec<String>,
+    /// Height in decimetres.
+    pub height: u16,
+    /// Weight in hectograms.
+    pub weight: u16,
+    /// The species this one evolves from, if any.
+    pub evolves_from: Option<u64>,
+}
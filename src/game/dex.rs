@@ -0,0 +1,338 @@
+use super::{
+    abilities::{AbilitySlots, PokemonAbility},
+    data::{self, error::DataError, result, DataFile, TypesEntry},
+    evolution::Evolution,
+    items::PokemonItem,
+    learnset::Learnset,
+    moves::{CategoryMechanic, PokemonMove, PriorityMechanic},
+    overlay::OverlayKind,
+    pokedex::PokedexData,
+    species::SpeciesInfo,
+    ElementType,
+};
+use std::collections::HashMap;
+use std::path::Path;
+
+lazy_static! {
+    static ref DEFAULT_DEX: result::Result<Dex> = Dex::load_default();
+}
+
+/// A complete set of game data: species, moves, items, abilities and types.
+///
+/// Most APIs have a variant taking a `Dex`, so several datasets can be used in one process.
+/// The convenience variants use the default instance returned by `Dex::global`.
+#[derive(Debug, Clone, Default)]
+pub struct Dex {
+    pub species: HashMap<usize, PokedexData>,
+    pub moves: HashMap<usize, PokemonMove>,
+    pub items: HashMap<usize, PokemonItem>,
+    pub types: HashMap<ElementType, TypesEntry>,
+    pub abilities: HashMap<usize, PokemonAbility>,
+    /// Evolutions, keyed by the species they evolve from.
+    pub evolutions: HashMap<usize, Vec<Evolution>>,
+    /// The mechanic used to resolve move categories.
+    pub category_mechanic: CategoryMechanic,
+    /// The mechanic giving moves their priority when the move data doesn't.
+    pub priority_mechanic: PriorityMechanic,
+    /// The overlay that provided each overridden or added entry.
+    pub overlay_sources: HashMap<(OverlayKind, usize), String>,
+}
+
+impl Dex {
+    /// Create an empty dex, to be filled by hand.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// The default dex, loaded once from the crate's data files.
+    pub fn global() -> result::Result<&'static Dex> {
+        (*DEFAULT_DEX).as_ref().map_err(|e| e.clone())
+    }
+    /// Load the crate's data files; see `data::load_data`.
+    pub fn load_default() -> result::Result<Self> {
+        let in_file = |file: DataFile| move |e: DataError| e.in_file(file.file_name());
+        let dex = Self {
+            species: PokedexData::from_entries(&data::load_data(DataFile::Pokedex)?)
+                .map_err(in_file(DataFile::Pokedex))?,
+            moves: PokemonMove::from_entries(&data::load_data(DataFile::Moves)?)
+                .map_err(in_file(DataFile::Moves))?,
+            items: PokemonItem::from_entries(&data::load_data(DataFile::Items)?),
+            types: ElementType::from_entries(&data::load_data(DataFile::Types)?)
+                .map_err(in_file(DataFile::Types))?,
+            evolutions: Evolution::from_entries(&data::load_data(DataFile::Evolutions)?)
+                .map_err(in_file(DataFile::Evolutions))?,
+            abilities: PokemonAbility::from_entries(&data::load_data(DataFile::Abilities)?),
+            ..Self::default()
+        };
+        let slots = AbilitySlots::from_entries(&data::load_data(DataFile::SpeciesAbilities)?);
+        dex.require_every_species(&slots)
+            .map_err(in_file(DataFile::SpeciesAbilities))?;
+        let info = SpeciesInfo::from_entries(&data::load_data(DataFile::SpeciesInfo)?)
+            .map_err(in_file(DataFile::SpeciesInfo))?;
+        dex.require_every_species(&info)
+            .map_err(in_file(DataFile::SpeciesInfo))?;
+        dex.check_evolutions(&info)
+            .map_err(in_file(DataFile::Evolutions))?;
+        let learnsets = Learnset::from_entries(&data::load_data(DataFile::Learnsets)?);
+        dex.require_every_species(&learnsets)
+            .map_err(in_file(DataFile::Learnsets))?;
+        Ok(dex
+            .with_learnsets(learnsets)
+            .with_ability_slots(slots)
+            .with_species_info(info))
+    }
+    /// Load a dataset from a directory laid out like the crate's `data` directory.
+    /// `learnsets.json`, `evolutions.json`, `abilities.json`, `species_abilities.json`
+    /// and `species_info.json` are optional, but `learnsets.json`,
+    /// `species_abilities.json` and `species_info.json` must cover every species
+    /// when present.
+    pub fn load<P: AsRef<Path>>(dir: P) -> result::Result<Self> {
+        let dir = dir.as_ref();
+        let dex = Self {
+            species: PokedexData::load(dir.join(DataFile::Pokedex.file_name()))?,
+            moves: PokemonMove::load(dir.join(DataFile::Moves.file_name()))?,
+            items: PokemonItem::load(dir.join(DataFile::Items.file_name()))?,
+            types: {
+                let path = dir.join(DataFile::Types.file_name());
+                ElementType::from_entries(&data::load_json(&path)?)
+                    .map_err(|e| e.in_file(&path.display().to_string()))?
+            },
+            evolutions: {
+                let path = dir.join(DataFile::Evolutions.file_name());
+                Evolution::from_entries(&data::load_optional_json(&path)?)
+                    .map_err(|e| e.in_file(&path.display().to_string()))?
+            },
+            abilities: PokemonAbility::from_entries(&data::load_optional_json(
+                dir.join(DataFile::Abilities.file_name()),
+            )?),
+            ..Self::default()
+        };
+        let slots = {
+            let path = dir.join(DataFile::SpeciesAbilities.file_name());
+            let slots = AbilitySlots::from_entries(&data::load_optional_json(&path)?);
+            if !slots.is_empty() {
+                dex.require_every_species(&slots)
+                    .map_err(|e| e.in_file(&path.display().to_string()))?;
+            }
+            slots
+        };
+        let info = {
+            let path = dir.join(DataFile::SpeciesInfo.file_name());
+            let info = SpeciesInfo::from_entries(&data::load_optional_json(&path)?)
+                .map_err(|e| e.in_file(&path.display().to_string()))?;
+            if !info.is_empty() {
+                dex.require_every_species(&info)
+                    .map_err(|e| e.in_file(&path.display().to_string()))?;
+            }
+            info
+        };
+        dex.check_evolutions(&info).map_err(|e| {
+            e.in_file(
+                &dir.join(DataFile::Evolutions.file_name())
+                    .display()
+                    .to_string(),
+            )
+        })?;
+        let learnsets = {
+            let path = dir.join(DataFile::Learnsets.file_name());
+            let learnsets = Learnset::from_entries(&data::load_optional_json(&path)?);
+            if !learnsets.is_empty() {
+                dex.require_every_species(&learnsets)
+                    .map_err(|e| e.in_file(&path.display().to_string()))?;
+            }
+            learnsets
+        };
+        Ok(dex
+            .with_learnsets(learnsets)
+            .with_ability_slots(slots)
+            .with_species_info(info))
+    }
+    /// Fail on the lowest species ID with no entry in a per-species file.
+    fn require_every_species<T>(&self, entries: &HashMap<usize, T>) -> result::Result<()> {
+        match self
+            .species
+            .keys()
+            .filter(|id| !entries.contains_key(id))
+            .min()
+        {
+            Some(id) => Err(DataError::invalid(format!("Species {} has no entry.", id))),
+            None => Ok(()),
+        }
+    }
+    /// Fail on evolutions between unknown species, and on evolutions that disagree
+    /// with the `evolves_from` species metadata in either direction.
+    fn check_evolutions(&self, info: &HashMap<usize, SpeciesInfo>) -> result::Result<()> {
+        let mut evolutions: Vec<&Evolution> = self.evolutions.values().flatten().collect();
+        evolutions.sort_by_key(|evo| (evo.from, evo.to));
+        for evo in evolutions {
+            self.get_species(evo.from)?;
+            self.get_species(evo.to)?;
+            if let Some(entry) = info.get(&evo.to) {
+                if entry.evolves_from != Some(evo.from) {
+                    return Err(DataError::invalid(format!(
+                        "Evolution from {} to {} doesn't match species {}'s evolves_from.",
+                        evo.from, evo.to, evo.to
+                    )));
+                }
+            }
+        }
+        let mut ids: Vec<&usize> = info.keys().collect();
+        ids.sort();
+        for id in ids {
+            if let Some(from) = info[id].evolves_from {
+                if !self.get_evolutions(from).iter().any(|evo| evo.to == *id) {
+                    return Err(DataError::invalid(format!(
+                        "Species {} evolves from {} but has no evolution entry.",
+                        id, from
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+    /// Attach learnsets to their species, ignoring learnsets of unknown species.
+    pub fn with_learnsets(mut self, learnsets: HashMap<usize, Learnset>) -> Self {
+        for (id, learnset) in learnsets.into_iter() {
+            if let Some(species) = self.species.get_mut(&id) {
+                species.learnset = learnset;
+            }
+        }
+        self
+    }
+    /// Attach ability slots to their species, ignoring slots of unknown species.
+    pub fn with_ability_slots(mut self, slots: HashMap<usize, AbilitySlots>) -> Self {
+        for (id, abilities) in slots.into_iter() {
+            if let Some(species) = self.species.get_mut(&id) {
+                species.abilities = abilities;
+            }
+        }
+        self
+    }
+    /// Attach metadata to their species, ignoring metadata of unknown species.
+    pub fn with_species_info(mut self, info: HashMap<usize, SpeciesInfo>) -> Self {
+        for (id, info) in info.into_iter() {
+            if let Some(species) = self.species.get_mut(&id) {
+                species.info = Some(info);
+            }
+        }
+        self
+    }
+    pub fn get_species(&self, id: usize) -> result::Result<&PokedexData> {
+        self.species.get(&id).ok_or(DataError::UnknownSpecies(id))
+    }
+    pub fn get_move(&self, id: usize) -> result::Result<&PokemonMove> {
+        self.moves.get(&id).ok_or(DataError::UnknownMove(id))
+    }
+    pub fn get_item(&self, id: usize) -> result::Result<&PokemonItem> {
+        self.items.get(&id).ok_or(DataError::UnknownItem(id))
+    }
+    pub fn get_ability(&self, id: usize) -> result::Result<&PokemonAbility> {
+        self.abilities.get(&id).ok_or(DataError::UnknownAbility(id))
+    }
+    /// Look up an item by its English name, ignoring case.
+    /// Some key items share a name; the lowest ID is returned for those.
+    pub fn get_item_by_name(&self, name: &str) -> Option<&PokemonItem> {
+        self.items
+            .values()
+            .filter(|item| item.name.eq_ignore_ascii_case(name))
+            .min_by_key(|item| item.id)
+    }
+    /// The species `id` can evolve into.
+    pub fn get_evolutions(&self, id: usize) -> &[Evolution] {
+        self.evolutions
+            .get(&id)
+            .map(|evos| &evos[..])
+            .unwrap_or(&[])
+    }
+    pub fn get_type_names(&self, element: &ElementType) -> Option<&TypesEntry> {
+        self.types.get(element)
+    }
+    /// Re-resolve every move's category under a different mechanic.
+    pub fn set_category_mechanic(&mut self, mechanic: CategoryMechanic) {
+        self.category_mechanic = mechanic;
+        for mv in self.moves.values_mut() {
+            mv.set_category_mechanic(mechanic);
+        }
+    }
+    /// Re-resolve every move's priority under a different mechanic.
+    pub fn set_priority_mechanic(&mut self, mechanic: PriorityMechanic) {
+        self.priority_mechanic = mechanic;
+        for mv in self.moves.values_mut() {
+            mv.set_priority_mechanic(mechanic);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Dex;
+    use crate::game::{
+        data::error::DataError,
+        monster::{Monster, MonsterStats},
+        pokedex::PokedexData,
+        species::SpeciesInfo,
+        ElementType,
+    };
+    use std::collections::HashMap;
+
+    #[test]
+    fn custom_dex() {
+        let mut dex = Dex::new();
+        dex.species.insert(
+            1,
+            PokedexData {
+                id: 1,
+                name: "Testmon".to_string(),
+                element: (ElementType::Fairy, ElementType::Fairy),
+                base: MonsterStats { internal: [100; 6] },
+                learnset: Default::default(),
+                abilities: Default::default(),
+                info: None,
+            },
+        );
+        let mon = Monster::try_from_dex_in(&dex, 1).unwrap();
+        assert_eq!(mon.base_name, "Testmon");
+        assert_eq!(mon.get_move_in(&dex, 0), None);
+        assert_eq!(mon.ability, None);
+        assert_eq!(
+            dex.require_every_species(&HashMap::<usize, ()>::new())
+                .unwrap_err(),
+            DataError::invalid("Species 1 has no entry.".to_string())
+        );
+        assert_eq!(
+            Monster::try_from_dex_in(&dex, 2).unwrap_err(),
+            DataError::UnknownSpecies(2)
+        );
+        // The default instance is unaffected.
+        assert_eq!(Monster::from_dex(1).base_name, "Bulbasaur");
+        assert!(Dex::global().unwrap().get_move(33).is_ok());
+    }
+
+    #[test]
+    fn evolutions_match_species_info() {
+        let global = Dex::global().unwrap();
+        let info: HashMap<usize, SpeciesInfo> = global
+            .species
+            .iter()
+            .filter_map(|(id, species)| species.info.clone().map(|info| (*id, info)))
+            .collect();
+        assert!(global.check_evolutions(&info).is_ok());
+        assert_eq!(info[&2].evolves_from, Some(1));
+        assert_eq!(info[&1].evolves_from, None);
+
+        let mut dex = global.clone();
+        dex.evolutions.get_mut(&1).unwrap().clear();
+        assert_eq!(
+            dex.check_evolutions(&info).unwrap_err(),
+            DataError::invalid("Species 2 evolves from 1 but has no evolution entry.".to_string())
+        );
+        let mut dex = global.clone();
+        dex.evolutions.get_mut(&1).unwrap()[0].to = 3;
+        assert!(dex.check_evolutions(&info).is_err());
+        let mut dex = global.clone();
+        dex.evolutions.get_mut(&1).unwrap()[0].to = 9999;
+        assert_eq!(
+            dex.check_evolutions(&info).unwrap_err(),
+            DataError::UnknownSpecies(9999)
+        );
+    }
+}
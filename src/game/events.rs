@@ -0,0 +1,109 @@
+use super::{
+    battle::BattleOutcome,
+    monster::{MonsterAttribute, MonsterStatus},
+};
+use enumflags2::BitFlags;
+use serde::{Deserialize, Serialize};
+
+/// Something that happened during a battle, in the order it happened.
+///
+/// `side` is the side of the monster the event happened to, and `index` is that
+/// monster's position in its team.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BattleEvent {
+    TurnStarted {
+        turn: u32,
+    },
+    /// A monster was sent out.
+    SwitchedIn {
+        side: usize,
+        index: usize,
+        species: usize,
+    },
+    MoveUsed {
+        side: usize,
+        index: usize,
+        move_id: usize,
+    },
+    /// The move used by this side's monster hit.
+    MoveHit {
+        side: usize,
+    },
+    /// The move used by this side's monster missed.
+    MoveMissed {
+        side: usize,
+    },
+    /// The move used by this side's monster had no effect.
+    MoveFailed {
+        side: usize,
+    },
+    /// This side's monster took a critical hit.
+    CriticalHit {
+        side: usize,
+    },
+    /// The type effectiveness of a move against this side's monster, when it isn't 1.
+    Effectiveness {
+        side: usize,
+        multiplier: f32,
+    },
+    Damage {
+        side: usize,
+        index: usize,
+        amount: u16,
+        hp_before: u16,
+        hp_after: u16,
+    },
+    Healed {
+        side: usize,
+        index: usize,
+        hp_before: u16,
+        hp_after: u16,
+    },
+    StatusApplied {
+        side: usize,
+        index: usize,
+        status: MonsterStatus,
+    },
+    /// This side's monster couldn't move because it is asleep or fully paralyzed.
+    StatusPrevented {
+        side: usize,
+        index: usize,
+        status: MonsterStatus,
+    },
+    StatusCured {
+        side: usize,
+        index: usize,
+        statuses: BitFlags<MonsterStatus>,
+    },
+    /// A stat stage changed by `change`, reaching `stage`.
+    StatStageChanged {
+        side: usize,
+        index: usize,
+        stat: MonsterAttribute,
+        change: i8,
+        stage: i8,
+    },
+    Fainted {
+        side: usize,
+        index: usize,
+    },
+    ItemUsed {
+        side: usize,
+        item: u16,
+        target: usize,
+    },
+    /// Trick Room started, reversing the Speed order for `turns` turns.
+    TrickRoomStarted {
+        turns: u8,
+    },
+    TrickRoomEnded,
+    EscapeFailed {
+        side: usize,
+    },
+    Fled {
+        side: usize,
+    },
+    BattleEnded {
+        outcome: BattleOutcome,
+    },
+}
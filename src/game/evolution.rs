@@ -0,0 +1,334 @@
+use super::{
+    data::{error::DataError, result, EvolutionEntry},
+    monster::{Monster, MonsterGender},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Friendship required by friendship-based evolutions.
+pub const EVOLUTION_FRIENDSHIP: u8 = 220;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TimeOfDay {
+    Day,
+    Night,
+}
+
+impl TryFrom<&str> for TimeOfDay {
+    type Error = DataError;
+
+    fn try_from(v: &str) -> result::Result<Self> {
+        match v {
+            "day" => Ok(TimeOfDay::Day),
+            "night" => Ok(TimeOfDay::Night),
+            _ => Err(DataError::invalid(format!("Unknown time of day: {}.", v))),
+        }
+    }
+}
+
+/// What causes a species to evolve.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EvolutionTrigger {
+    /// Level up to at least this level.
+    Level(u8),
+    /// Use this item on the monster.
+    Item(u16),
+    /// Trade the monster, optionally while it holds an item.
+    Trade(Option<u16>),
+    /// Level up with high friendship.
+    Friendship,
+    /// Level up while holding this item.
+    HeldItem(u16),
+    /// Level up while knowing this move.
+    KnownMove(u16),
+    /// A requirement the engine doesn't track, such as the party, the weather or the
+    /// game version, described in words. It is never met automatically, but the
+    /// evolution can still be applied with `Monster::evolve`.
+    Other(String),
+}
+
+/// A single evolution from one species into another.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Evolution {
+    pub from: usize,
+    pub to: usize,
+    pub trigger: EvolutionTrigger,
+    /// The time of day the evolution is restricted to, if any.
+    pub time: Option<TimeOfDay>,
+    /// The gender the evolution is restricted to, if any.
+    pub gender: Option<MonsterGender>,
+}
+
+/// The situation an evolution is checked in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvolutionContext {
+    /// The monster just leveled up.
+    pub level_up: bool,
+    /// The item being used on the monster.
+    pub used_item: Option<u16>,
+    /// The monster was just traded.
+    pub traded: bool,
+    pub time: TimeOfDay,
+}
+
+impl EvolutionContext {
+    pub fn level_up(time: TimeOfDay) -> Self {
+        Self {
+            level_up: true,
+            used_item: None,
+            traded: false,
+            time,
+        }
+    }
+    pub fn use_item(item: u16, time: TimeOfDay) -> Self {
+        Self {
+            level_up: false,
+            used_item: Some(item),
+            traded: false,
+            time,
+        }
+    }
+    pub fn trade(time: TimeOfDay) -> Self {
+        Self {
+            level_up: false,
+            used_item: None,
+            traded: true,
+            time,
+        }
+    }
+}
+
+impl Evolution {
+    /// Convert an evolution entry, failing on an unknown trigger, time of day or gender,
+    /// or when the trigger's level, item, move or condition is missing.
+    pub fn from_json(obj: &EvolutionEntry) -> result::Result<Self> {
+        let missing = |what: &str| {
+            DataError::invalid(format!(
+                "Evolution from {} to {} has no {}.",
+                obj.from, obj.to, what
+            ))
+        };
+        let item = obj.item.map(|id| id as u16);
+        let trigger = match obj.trigger.as_str() {
+            "level" => EvolutionTrigger::Level(
+                obj.level
+                    .filter(|level| *level > 0)
+                    .ok_or_else(|| missing("level"))?,
+            ),
+            "item" => EvolutionTrigger::Item(item.ok_or_else(|| missing("item"))?),
+            "trade" => EvolutionTrigger::Trade(item),
+            "friendship" => EvolutionTrigger::Friendship,
+            "held_item" => EvolutionTrigger::HeldItem(item.ok_or_else(|| missing("item"))?),
+            "known_move" => EvolutionTrigger::KnownMove(
+                obj.known_move
+                    .map(|id| id as u16)
+                    .ok_or_else(|| missing("move"))?,
+            ),
+            "other" => {
+                EvolutionTrigger::Other(obj.condition.clone().ok_or_else(|| missing("condition"))?)
+            }
+            other => {
+                return Err(DataError::invalid(format!(
+                    "Unknown evolution trigger: {}.",
+                    other
+                )))
+            }
+        };
+        Ok(Self {
+            from: obj.from as usize,
+            to: obj.to as usize,
+            trigger,
+            time: obj.time.as_deref().map(TimeOfDay::try_from).transpose()?,
+            gender: match obj.gender.as_deref() {
+                None => None,
+                Some("male") => Some(MonsterGender::Male),
+                Some("female") => Some(MonsterGender::Female),
+                Some(other) => {
+                    return Err(DataError::invalid(format!(
+                        "Unknown evolution gender: {}.",
+                        other
+                    )))
+                }
+            },
+        })
+    }
+    /// Group evolutions by the species they evolve from.
+    pub fn from_entries(entries: &[EvolutionEntry]) -> result::Result<HashMap<usize, Vec<Self>>> {
+        let mut map: HashMap<usize, Vec<Evolution>> = HashMap::new();
+        for obj in entries.iter() {
+            let entry = Evolution::from_json(obj)?;
+            map.entry(entry.from).or_default().push(entry);
+        }
+        Ok(map)
+    }
+    /// Whether `monster` meets this evolution's conditions in `ctx`.
+    pub fn is_satisfied(&self, monster: &Monster, ctx: &EvolutionContext) -> bool {
+        if monster.pid != self.from {
+            return false;
+        }
+        if let Some(time) = self.time {
+            if time != ctx.time {
+                return false;
+            }
+        }
+        if self
+            .gender
+            .as_ref()
+            .is_some_and(|gender| *gender != monster.gender)
+        {
+            return false;
+        }
+        match &self.trigger {
+            EvolutionTrigger::Level(level) => ctx.level_up && monster.level >= *level,
+            EvolutionTrigger::Item(item) => ctx.used_item == Some(*item),
+            EvolutionTrigger::Trade(item) => {
+                ctx.traded && (item.is_none() || monster.held_item == *item)
+            }
+            EvolutionTrigger::Friendship => {
+                ctx.level_up && monster.friendship >= EVOLUTION_FRIENDSHIP
+            }
+            EvolutionTrigger::HeldItem(item) => ctx.level_up && monster.held_item == Some(*item),
+            EvolutionTrigger::KnownMove(id) => {
+                let (a, b, c, d) = monster.moves;
+                ctx.level_up && [a, b, c, d].contains(id)
+            }
+            EvolutionTrigger::Other(_) => false,
+        }
+    }
+    /// Whether evolving consumes the monster's held item.
+    pub fn consumes_held_item(&self) -> bool {
+        matches!(
+            self.trigger,
+            EvolutionTrigger::Trade(Some(_)) | EvolutionTrigger::HeldItem(_)
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Evolution, EvolutionContext, EvolutionTrigger, TimeOfDay};
+    use crate::game::{
+        data::{error::DataError, EvolutionEntry},
+        dex::Dex,
+        monster::{Monster, MonsterAttribute, MonsterGender},
+        ElementType,
+    };
+
+    fn entry(trigger: &str, level: Option<u8>, item: Option<u64>) -> EvolutionEntry {
+        EvolutionEntry {
+            from: 1,
+            to: 2,
+            trigger: trigger.to_string(),
+            level,
+            item,
+            time: None,
+            gender: None,
+            known_move: None,
+            condition: None,
+        }
+    }
+
+    #[test]
+    fn invalid_entries() {
+        assert_eq!(
+            Evolution::from_json(&entry("level", Some(16), None))
+                .unwrap()
+                .trigger,
+            EvolutionTrigger::Level(16)
+        );
+        // A level evolution without a level must not fire at level 1.
+        assert_eq!(
+            Evolution::from_json(&entry("level", None, None)).unwrap_err(),
+            DataError::invalid("Evolution from 1 to 2 has no level.".to_string())
+        );
+        assert!(Evolution::from_json(&entry("item", None, None)).is_err());
+        assert!(Evolution::from_json(&entry("held_item", None, None)).is_err());
+        assert!(Evolution::from_json(&entry("moon", None, None)).is_err());
+        assert!(Evolution::from_json(&entry("known_move", None, None)).is_err());
+        assert!(Evolution::from_json(&entry("other", None, None)).is_err());
+        let mut unknown_gender = entry("level", Some(20), None);
+        unknown_gender.gender = Some("both".to_string());
+        assert!(Evolution::from_json(&unknown_gender).is_err());
+        let mut at_dusk = entry("friendship", None, None);
+        at_dusk.time = Some("dusk".to_string());
+        assert!(Evolution::from_entries(&[at_dusk]).is_err());
+    }
+
+    #[test]
+    fn evolve() {
+        let mut bulbasaur = Monster::from_dex(1);
+        bulbasaur.nickname = Some("Bulby".to_string());
+        bulbasaur.set_level(15);
+        let ctx = EvolutionContext::level_up(TimeOfDay::Day);
+        assert!(bulbasaur.pending_evolutions(&ctx).is_empty());
+        bulbasaur.set_level(16);
+        let pending = bulbasaur.pending_evolutions(&ctx);
+        assert_eq!(pending.len(), 1);
+
+        let before = bulbasaur.clone();
+        bulbasaur.evolve(pending[0].to).unwrap();
+        assert_eq!(bulbasaur.pid, 2);
+        assert_eq!(bulbasaur.base_name, "Ivysaur");
+        assert_eq!(bulbasaur.base_stats.get(MonsterAttribute::HP), 60);
+        assert_eq!(
+            bulbasaur.elements,
+            (ElementType::Grass, ElementType::Poison)
+        );
+        assert_eq!(bulbasaur.uuid, before.uuid);
+        assert_eq!(bulbasaur.nickname, before.nickname);
+        assert_eq!(bulbasaur.iv, before.iv);
+        assert_eq!(bulbasaur.ev, before.ev);
+        assert_eq!(bulbasaur.nature, before.nature);
+        assert_eq!(bulbasaur.ability, before.ability);
+        // Venusaur is not a direct evolution of Bulbasaur.
+        assert!(Monster::from_dex(1).evolve(3).is_err());
+    }
+
+    #[test]
+    fn evolution_triggers() {
+        let mut eevee = Monster::from_dex(133);
+        eevee.friendship = 220;
+        let night = EvolutionContext::level_up(TimeOfDay::Night);
+        let targets: Vec<usize> = eevee
+            .pending_evolutions(&night)
+            .iter()
+            .map(|evo| evo.to)
+            .collect();
+        assert_eq!(targets, vec![197]);
+        let stone = EvolutionContext::use_item(82, TimeOfDay::Day);
+        assert_eq!(eevee.pending_evolutions(&stone)[0].to, 136);
+
+        let mut onix = Monster::from_dex(95);
+        let trade = EvolutionContext::trade(TimeOfDay::Day);
+        assert!(onix.pending_evolutions(&trade).is_empty());
+        onix.held_item = Some(233);
+        assert_eq!(onix.pending_evolutions(&trade)[0].to, 208);
+        onix.evolve(208).unwrap();
+        assert_eq!(onix.held_item, None);
+
+        let mut kirlia = Monster::from_dex(281);
+        let dawn_stone = EvolutionContext::use_item(109, TimeOfDay::Day);
+        kirlia.gender = MonsterGender::Female;
+        assert!(kirlia.pending_evolutions(&dawn_stone).is_empty());
+        kirlia.gender = MonsterGender::Male;
+        assert_eq!(kirlia.pending_evolutions(&dawn_stone)[0].to, 475);
+
+        let mut aipom = Monster::from_dex(190);
+        let day = EvolutionContext::level_up(TimeOfDay::Day);
+        aipom.moves = (10, 0, 0, 0);
+        assert!(aipom.pending_evolutions(&day).is_empty());
+        aipom.moves = (10, 458, 0, 0);
+        assert_eq!(aipom.pending_evolutions(&day)[0].to, 424);
+
+        // Untracked requirements never fire, but can be applied by hand.
+        let mut mantyke = Monster::from_dex(458);
+        mantyke.set_level(100);
+        assert!(mantyke.pending_evolutions(&day).is_empty());
+        assert!(matches!(
+            Dex::global().unwrap().get_evolutions(458)[0].trigger,
+            EvolutionTrigger::Other(_)
+        ));
+        mantyke.evolve(226).unwrap();
+        assert_eq!(mantyke.pid, 226);
+    }
+}
@@ -0,0 +1,203 @@
+use super::{
+    dex::Dex,
+    monster::{Monster, MonsterAttribute, MonsterStats},
+    species::{GrowthRate, SpeciesInfo},
+};
+use serde::{Deserialize, Serialize};
+
+/// The highest level a monster can reach.
+pub const MAX_LEVEL: u8 = 100;
+
+impl GrowthRate {
+    /// The total experience needed to reach `level`.
+    pub fn exp_for_level(&self, level: u8) -> u32 {
+        let n = level.clamp(1, MAX_LEVEL) as i64;
+        if n == 1 {
+            return 0;
+        }
+        let cube = n * n * n;
+        let exp = match self {
+            GrowthRate::Fast => cube * 4 / 5,
+            GrowthRate::MediumFast => cube,
+            GrowthRate::MediumSlow => cube * 6 / 5 - 15 * n * n + 100 * n - 140,
+            GrowthRate::Slow => cube * 5 / 4,
+            GrowthRate::Erratic => match n {
+                n if n < 50 => cube * (100 - n) / 50,
+                n if n < 68 => cube * (150 - n) / 100,
+                n if n < 98 => cube * ((1911 - 10 * n) / 3) / 500,
+                n => cube * (160 - n) / 100,
+            },
+            GrowthRate::Fluctuating => match n {
+                n if n < 15 => cube * ((n + 1) / 3 + 24) / 50,
+                n if n < 36 => cube * (n + 14) / 50,
+                n => cube * (n / 2 + 32) / 50,
+            },
+        };
+        exp as u32
+    }
+    /// The level reached with `exp` total experience.
+    pub fn level_for_exp(&self, exp: u32) -> u8 {
+        let mut level = 1;
+        while level < MAX_LEVEL && self.exp_for_level(level + 1) <= exp {
+            level += 1;
+        }
+        level
+    }
+}
+
+impl SpeciesInfo {
+    /// The experience yielded by defeating a monster of this species at `level`,
+    /// using the Gen3 formula. Trainer-owned monsters yield 1.5 times as much.
+    pub fn exp_yield(&self, level: u8, trainer: bool) -> u32 {
+        let exp = self.base_exp as u32 * level as u32 / 7;
+        if trainer {
+            exp * 3 / 2
+        } else {
+            exp
+        }
+    }
+}
+
+/// A single level gained by a monster.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LevelUp {
+    /// The level reached.
+    pub level: u8,
+    /// How much each stat increased, indexed like `MonsterStats`.
+    pub stat_deltas: MonsterStats,
+    /// Moves the species learns at this level.
+    pub new_moves: Vec<u16>,
+}
+
+impl Monster {
+    /// This monster's growth rate in the given dex.
+    /// Species without metadata use Medium Fast.
+    pub fn growth_rate_in(&self, dex: &Dex) -> GrowthRate {
+        dex.get_species(self.pid)
+            .ok()
+            .and_then(|data| data.info.as_ref())
+            .map(|info| info.growth_rate)
+            .unwrap_or_default()
+    }
+    /// Add experience, processing every level gained.
+    pub fn gain_xp(&mut self, amount: u32) -> Vec<LevelUp> {
+        match Dex::global() {
+            Ok(dex) => self.gain_xp_in(dex, amount),
+            Err(_) => self.gain_xp_in(&Dex::new(), amount),
+        }
+    }
+    /// Add experience using the given dex, processing every level gained.
+    /// Stats are recomputed after each level and current HP rises by the max HP gained.
+    /// Experience is capped at the total for level 100.
+    pub fn gain_xp_in(&mut self, dex: &Dex, amount: u32) -> Vec<LevelUp> {
+        let growth = self.growth_rate_in(dex);
+        self.xp = self
+            .xp
+            .saturating_add(amount)
+            .min(growth.exp_for_level(MAX_LEVEL));
+        let mut events: Vec<LevelUp> = Vec::new();
+        while self.level < MAX_LEVEL && self.xp >= growth.exp_for_level(self.level + 1) {
+            let before = self.stats.clone();
+            self.level += 1;
+            self.recompute_stats();
+            let mut stat_deltas = MonsterStats::new();
+            for i in 0..6u8 {
+                let attr = MonsterAttribute::from(i);
+                let delta = self
+                    .get_stat(attr.clone())
+                    .saturating_sub(before.get(attr.clone()));
+                stat_deltas.set(attr, delta.min(u8::MAX as u16) as u8);
+            }
+            let new_moves = dex
+                .get_species(self.pid)
+                .map(|data| data.learnset.moves_at_level(self.level))
+                .unwrap_or_default();
+            events.push(LevelUp {
+                level: self.level,
+                stat_deltas,
+                new_moves,
+            });
+        }
+        self.next_xp = growth.exp_for_level((self.level + 1).min(MAX_LEVEL));
+        events
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::MAX_LEVEL;
+    use crate::game::{
+        builder::MonsterBuilder,
+        dex::Dex,
+        monster::{Monster, MonsterAttribute},
+        species::GrowthRate,
+    };
+
+    #[test]
+    fn growth_curves() {
+        let totals = [
+            (GrowthRate::Erratic, 600000),
+            (GrowthRate::Fast, 800000),
+            (GrowthRate::MediumFast, 1000000),
+            (GrowthRate::MediumSlow, 1059860),
+            (GrowthRate::Slow, 1250000),
+            (GrowthRate::Fluctuating, 1640000),
+        ];
+        for (growth, total) in totals.iter() {
+            assert_eq!(growth.exp_for_level(1), 0);
+            assert_eq!(growth.exp_for_level(MAX_LEVEL), *total);
+            assert_eq!(growth.level_for_exp(*total), MAX_LEVEL);
+        }
+        assert_eq!(GrowthRate::MediumSlow.exp_for_level(2), 9);
+        assert_eq!(GrowthRate::MediumSlow.exp_for_level(16), 2535);
+        assert_eq!(GrowthRate::Erratic.exp_for_level(50), 125000);
+        assert_eq!(GrowthRate::Fluctuating.exp_for_level(36), 46656);
+    }
+
+    #[test]
+    fn gain_xp() {
+        let mut bulbasaur = Monster::from_dex(1);
+        assert_eq!(bulbasaur.next_xp, 9);
+        let events = bulbasaur.gain_xp(GrowthRate::MediumSlow.exp_for_level(15));
+        assert_eq!(events.len(), 14);
+        assert_eq!(bulbasaur.level, 15);
+        assert_eq!(events.last().unwrap().new_moves, vec![77, 79]);
+        assert_eq!(bulbasaur.hp, bulbasaur.max_hp());
+        let gained: u16 = events
+            .iter()
+            .map(|event| event.stat_deltas.get(MonsterAttribute::ATT) as u16)
+            .sum();
+        let mut fresh = bulbasaur.clone();
+        fresh.set_level(1);
+        assert_eq!(
+            gained,
+            bulbasaur.get_stat(MonsterAttribute::ATT) - fresh.get_stat(MonsterAttribute::ATT)
+        );
+
+        // Damage carries over, and experience stops at level 100.
+        bulbasaur.hp -= 10;
+        bulbasaur.gain_xp(u32::MAX);
+        assert_eq!(bulbasaur.level, MAX_LEVEL);
+        assert_eq!(bulbasaur.xp, 1059860);
+        assert_eq!(bulbasaur.hp, bulbasaur.max_hp() - 10);
+        assert!(bulbasaur.gain_xp(1000).is_empty());
+    }
+
+    #[test]
+    fn growth_rate_from_dex() {
+        let mut dex = Dex::global().unwrap().clone();
+        let info = dex.species.get_mut(&1).unwrap().info.as_mut().unwrap();
+        info.growth_rate = GrowthRate::Fast;
+        let mut bulbasaur = Monster::from_dex(1);
+        bulbasaur.set_level_in(&dex, 20);
+        assert_eq!(bulbasaur.xp, GrowthRate::Fast.exp_for_level(20));
+        assert_eq!(bulbasaur.next_xp, GrowthRate::Fast.exp_for_level(21));
+        bulbasaur.set_level(20);
+        assert_eq!(bulbasaur.xp, GrowthRate::MediumSlow.exp_for_level(20));
+
+        let mut built = MonsterBuilder::new(1).level(20).build_in(&dex).unwrap();
+        assert_eq!(built.xp, GrowthRate::Fast.exp_for_level(20));
+        built.gain_xp_in(&dex, GrowthRate::Fast.exp_for_level(21) - built.xp);
+        assert_eq!(built.level, 21);
+    }
+}
@@ -0,0 +1,74 @@
+use super::data::{self, result, ItemsEntry};
+use super::dex::Dex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PokemonItem {
+    pub id: usize,
+    pub name: String,
+}
+
+impl PokemonItem {
+    pub fn get_item(id: usize) -> Option<Self> {
+        Self::try_get_item(id).ok()
+    }
+    pub fn try_get_item(id: usize) -> result::Result<Self> {
+        Dex::global()?.get_item(id).cloned()
+    }
+    /// Look up an item by its English name, ignoring case.
+    pub fn get_item_by_name(name: &str) -> Option<Self> {
+        Dex::global().ok()?.get_item_by_name(name).cloned()
+    }
+    /// Load an items file, keyed by item ID.
+    pub fn load<P: AsRef<Path>>(path: P) -> result::Result<HashMap<usize, Self>> {
+        let pjson: Vec<ItemsEntry> = data::load_json(path)?;
+        Ok(Self::from_entries(&pjson))
+    }
+    pub fn from_entries(entries: &[ItemsEntry]) -> HashMap<usize, Self> {
+        let mut map: HashMap<usize, PokemonItem> = HashMap::new();
+        for obj in entries.iter() {
+            // Item 0 is the "None" placeholder and is represented by an empty held item.
+            if obj.id == 0 {
+                continue;
+            }
+            let entry = PokemonItem::from_json(obj);
+            map.insert(entry.id, entry);
+        }
+        map
+    }
+    pub fn from_json(obj: &ItemsEntry) -> Self {
+        Self {
+            id: obj.id as usize,
+            name: obj.name.english.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PokemonItem;
+    use crate::game::{
+        data::error::DataError,
+        monster::{error::MonsterError, Monster},
+    };
+
+    #[test]
+    fn get_item() {
+        let leftovers = PokemonItem::get_item_by_name("leftovers").unwrap();
+        assert_eq!(leftovers.id, 234);
+        assert_eq!(PokemonItem::get_item(234), Some(leftovers.clone()));
+        assert!(PokemonItem::get_item(0).is_none());
+
+        let mut monster = Monster::from_dex(1);
+        assert!(monster.get_held_item().is_none());
+        monster.set_held_item(Some(234)).unwrap();
+        assert_eq!(monster.get_held_item(), Some(leftovers));
+        assert!(matches!(
+            monster.set_held_item(Some(9999)),
+            Err(MonsterError::Data(DataError::UnknownItem(9999)))
+        ));
+        assert_eq!(monster.held_item, Some(234));
+    }
+}
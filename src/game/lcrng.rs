@@ -0,0 +1,241 @@
+use super::{
+    dex::Dex,
+    monster::{Monster, MonsterAttribute, MonsterStats},
+    personality::Personality,
+};
+use serde::{Deserialize, Serialize};
+
+const LCRNG_MULT: u32 = 0x41C6_4E6D;
+const LCRNG_ADD: u32 = 0x0000_6073;
+const LCRNG_REV_MULT: u32 = 0xEEB9_EB65;
+const LCRNG_REV_ADD: u32 = 0x0A35_61A1;
+
+/// The linear congruential generator used by the Gen3 games.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Lcrng {
+    pub seed: u32,
+}
+
+impl Lcrng {
+    pub fn new(seed: u32) -> Self {
+        Self { seed }
+    }
+    /// Advance one frame and return the new state.
+    pub fn next_u32(&mut self) -> u32 {
+        self.seed = self.seed.wrapping_mul(LCRNG_MULT).wrapping_add(LCRNG_ADD);
+        self.seed
+    }
+    /// Advance one frame and return the high 16 bits of the new state,
+    /// which is the value the games use.
+    pub fn next_u16(&mut self) -> u16 {
+        (self.next_u32() >> 16) as u16
+    }
+    /// Step back one frame and return the new state.
+    pub fn prev_u32(&mut self) -> u32 {
+        self.seed = self
+            .seed
+            .wrapping_mul(LCRNG_REV_MULT)
+            .wrapping_add(LCRNG_REV_ADD);
+        self.seed
+    }
+    pub fn advance(&mut self, frames: u32) {
+        for _ in 0..frames {
+            self.next_u32();
+        }
+    }
+    pub fn reverse(&mut self, frames: u32) {
+        for _ in 0..frames {
+            self.prev_u32();
+        }
+    }
+}
+
+/// The Gen3 algorithms for generating a wild or static encounter's PID and IVs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GenerationMethod {
+    /// PID, then both IV halves.
+    Method1,
+    /// PID, a skipped frame, then both IV halves.
+    Method2,
+    /// PID, the first IV half, a skipped frame, then the second IV half.
+    Method4,
+}
+
+/// A PID and IV spread produced from a seed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Spread {
+    /// The state before the first call.
+    pub seed: u32,
+    pub method: GenerationMethod,
+    pub personality: Personality,
+    pub ivs: MonsterStats,
+}
+
+impl GenerationMethod {
+    /// Frames skipped before the first and second IV halves.
+    fn gaps(&self) -> (u32, u32) {
+        match self {
+            GenerationMethod::Method1 => (0, 0),
+            GenerationMethod::Method2 => (1, 0),
+            GenerationMethod::Method4 => (0, 1),
+        }
+    }
+    /// Generate the spread starting from `seed`.
+    pub fn generate(&self, seed: u32) -> Spread {
+        let (first_gap, second_gap) = self.gaps();
+        let mut rng = Lcrng::new(seed);
+        let low = rng.next_u16() as u32;
+        let high = rng.next_u16() as u32;
+        rng.advance(first_gap);
+        let first = rng.next_u16();
+        rng.advance(second_gap);
+        let second = rng.next_u16();
+        Spread {
+            seed,
+            method: *self,
+            personality: Personality((high << 16) | low),
+            ivs: ivs_from_halves(first, second),
+        }
+    }
+    /// Every spread from `seed` onwards, one per frame.
+    pub fn frames(&self, seed: u32) -> impl Iterator<Item = Spread> {
+        let method = *self;
+        let mut rng = Lcrng::new(seed);
+        std::iter::from_fn(move || {
+            let spread = method.generate(rng.seed);
+            rng.next_u32();
+            Some(spread)
+        })
+    }
+    /// Every seed that generates this PID and IV spread, in ascending order.
+    pub fn seeds_for(&self, personality: Personality, ivs: &MonsterStats) -> Vec<u32> {
+        let (first_gap, second_gap) = self.gaps();
+        let (first, second) = ivs_to_halves(ivs);
+        let low = personality.0 & 0xFFFF;
+        let high = (personality.0 >> 16) as u16;
+        let mut seeds: Vec<u32> = Vec::new();
+        // The first call's output fixes the top half of its state; try every bottom half.
+        for bottom in 0..=0xFFFFu32 {
+            let state = (low << 16) | bottom;
+            let mut rng = Lcrng::new(state);
+            if rng.next_u16() != high {
+                continue;
+            }
+            rng.advance(first_gap);
+            if rng.next_u16() & 0x7FFF != first {
+                continue;
+            }
+            rng.advance(second_gap);
+            if rng.next_u16() & 0x7FFF != second {
+                continue;
+            }
+            let mut rng = Lcrng::new(state);
+            seeds.push(rng.prev_u32());
+        }
+        seeds.sort_unstable();
+        seeds
+    }
+}
+
+/// Unpack IVs from the two 16-bit values the games draw them from:
+/// HP, Attack and Defense, then Speed, Sp. Attack and Sp. Defense, 5 bits each.
+pub fn ivs_from_halves(first: u16, second: u16) -> MonsterStats {
+    let mut ivs = MonsterStats::new();
+    for i in 0..3u8 {
+        let shift = 5 * i as u16;
+        ivs.set(MonsterAttribute::from(i), ((first >> shift) & 0x1F) as u8);
+        ivs.set(
+            MonsterAttribute::from(i + 3),
+            ((second >> shift) & 0x1F) as u8,
+        );
+    }
+    ivs
+}
+
+/// Pack IVs into the low 15 bits of their two 16-bit values.
+pub fn ivs_to_halves(ivs: &MonsterStats) -> (u16, u16) {
+    let mut first = 0u16;
+    let mut second = 0u16;
+    for i in 0..3u8 {
+        let shift = 5 * i as u16;
+        first |= ((ivs.get(MonsterAttribute::from(i)) & 0x1F) as u16) << shift;
+        second |= ((ivs.get(MonsterAttribute::from(i + 3)) & 0x1F) as u16) << shift;
+    }
+    (first, second)
+}
+
+impl Monster {
+    /// Apply a generated spread's personality value and IVs.
+    pub fn apply_spread(&mut self, spread: &Spread) {
+        self.iv = spread.ivs.clone();
+        self.set_personality(spread.personality);
+    }
+    /// Apply a generated spread's personality value and IVs using species data from the given dex.
+    pub fn apply_spread_in(&mut self, dex: &Dex, spread: &Spread) {
+        self.iv = spread.ivs.clone();
+        self.set_personality_in(dex, spread.personality);
+    }
+    /// Every seed that generates this monster's personality value and IVs with `method`.
+    pub fn candidate_seeds(&self, method: GenerationMethod) -> Vec<u32> {
+        method.seeds_for(self.personality, &self.iv)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ivs_from_halves, ivs_to_halves, GenerationMethod, Lcrng};
+    use crate::game::{monster::Monster, personality::Personality};
+
+    #[test]
+    fn lcrng() {
+        let mut rng = Lcrng::new(0);
+        let calls: Vec<u16> = (0..5).map(|_| rng.next_u16()).collect();
+        assert_eq!(calls, vec![0x0000, 0xE97E, 0x5271, 0x31B0, 0x8E42]);
+        rng.reverse(5);
+        assert_eq!(rng.seed, 0);
+        rng.prev_u32();
+        rng.advance(1);
+        assert_eq!(rng.seed, 0);
+
+        let ivs = ivs_from_halves(0x5271, 0x31B0);
+        assert_eq!(ivs.internal, [17, 19, 20, 16, 13, 12]);
+        assert_eq!(ivs_to_halves(&ivs), (0x5271, 0x31B0));
+    }
+
+    #[test]
+    fn generation_methods() {
+        let spread = GenerationMethod::Method1.generate(0);
+        assert_eq!(spread.personality, Personality(0xE97E_0000));
+        assert_eq!(spread.ivs.internal, [17, 19, 20, 16, 13, 12]);
+        let spread = GenerationMethod::Method2.generate(0);
+        assert_eq!(spread.ivs, ivs_from_halves(0x31B0, 0x8E42));
+        let spread = GenerationMethod::Method4.generate(0);
+        assert_eq!(spread.ivs, ivs_from_halves(0x5271, 0x8E42));
+
+        let frames: Vec<u32> = GenerationMethod::Method1
+            .frames(0)
+            .take(3)
+            .map(|spread| spread.seed)
+            .collect();
+        assert_eq!(frames, vec![0, 0x0000_6073, 0xE97E_7B6A]);
+
+        // Seed to monster and back.
+        let mut mon = Monster::from_dex(25);
+        for method in [
+            GenerationMethod::Method1,
+            GenerationMethod::Method2,
+            GenerationMethod::Method4,
+        ] {
+            let spread = method.generate(0xDEAD_BEEF);
+            mon.apply_spread(&spread);
+            assert_eq!(mon.personality, spread.personality);
+            assert_eq!(mon.nature, spread.personality.nature());
+            assert!(mon.candidate_seeds(method).contains(&0xDEAD_BEEF));
+        }
+        mon.iv.internal[0] ^= 1;
+        assert!(mon
+            .candidate_seeds(GenerationMethod::Method4)
+            .iter()
+            .all(|seed| *seed != 0xDEAD_BEEF));
+    }
+}
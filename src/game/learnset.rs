@@ -0,0 +1,146 @@
+use super::data::LearnsetEntry;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The moves a species can learn, by method.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Learnset {
+    /// Moves learned by level up as (level, move ID), in the order they are learned.
+    pub level_up: Vec<(u8, u16)>,
+    /// Moves learned from TMs and HMs.
+    pub tm: Vec<u16>,
+    /// Moves inherited through breeding.
+    pub egg: Vec<u16>,
+    /// Moves taught by move tutors.
+    pub tutor: Vec<u16>,
+}
+
+impl Learnset {
+    pub fn from_json(obj: &LearnsetEntry) -> Self {
+        let mut level_up: Vec<(u8, u16)> = obj
+            .level_up
+            .iter()
+            .map(|entry| (entry.level, entry._move as u16))
+            .collect();
+        // Stable, so moves sharing a level keep their data order.
+        level_up.sort_by_key(|entry| entry.0);
+        Self {
+            level_up,
+            tm: obj.tm.iter().map(|id| *id as u16).collect(),
+            egg: obj.egg.iter().map(|id| *id as u16).collect(),
+            tutor: obj.tutor.iter().map(|id| *id as u16).collect(),
+        }
+    }
+    pub fn from_entries(entries: &[LearnsetEntry]) -> HashMap<usize, Self> {
+        let mut map: HashMap<usize, Learnset> = HashMap::new();
+        for obj in entries.iter() {
+            map.insert(obj.id as usize, Learnset::from_json(obj));
+        }
+        map
+    }
+    /// Moves learned by level up at exactly `level`.
+    pub fn moves_at_level(&self, level: u8) -> Vec<u16> {
+        self.level_up
+            .iter()
+            .filter(|entry| entry.0 == level)
+            .map(|entry| entry.1)
+            .collect()
+    }
+    /// The moveset of a wild or newly generated monster at `level`:
+    /// the four most recent level-up moves, oldest first, with 0 for empty slots.
+    pub fn default_moves(&self, level: u8) -> (u16, u16, u16, u16) {
+        let mut moves: Vec<u16> = Vec::new();
+        for entry in self.level_up.iter().filter(|entry| entry.0 <= level) {
+            if moves.contains(&entry.1) {
+                continue;
+            }
+            if moves.len() == 4 {
+                moves.remove(0);
+            }
+            moves.push(entry.1);
+        }
+        moves.resize(4, 0);
+        (moves[0], moves[1], moves[2], moves[3])
+    }
+    /// Every move a monster of this species can know at `level`, without duplicates.
+    pub fn legal_moves(&self, level: u8) -> Vec<u16> {
+        let mut res: Vec<u16> = Vec::new();
+        let level_up = self
+            .level_up
+            .iter()
+            .filter(|entry| entry.0 <= level)
+            .map(|entry| entry.1);
+        let others = self
+            .tm
+            .iter()
+            .chain(self.egg.iter())
+            .chain(self.tutor.iter());
+        for id in level_up.chain(others.copied()) {
+            if !res.contains(&id) {
+                res.push(id);
+            }
+        }
+        res
+    }
+    /// Whether `move_id` can be known at `level` by any method.
+    pub fn can_learn(&self, move_id: u16, level: u8) -> bool {
+        self.legal_moves(level).contains(&move_id)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::game::{monster::Monster, pokedex::PokedexData};
+
+    #[test]
+    fn default_moves() {
+        let bulbasaur = PokedexData::get_pokemon(1).unwrap();
+        let learnset = bulbasaur.learnset();
+        assert_eq!(learnset.default_moves(1), (33, 0, 0, 0));
+        // Tackle, Growl, Leech Seed, Vine Whip, then both powders at 15.
+        assert_eq!(learnset.default_moves(15), (73, 22, 77, 79));
+        assert_eq!(learnset.moves_at_level(15), vec![77, 79]);
+        assert!(learnset.can_learn(76, 5));
+        assert!(!learnset.can_learn(76 + 1000, 100));
+
+        let mut charmander = Monster::from_dex(4);
+        assert_eq!(charmander.moves, (10, 45, 0, 0));
+        charmander.set_level(13);
+        assert_eq!(charmander.default_moves_for_level(), (10, 45, 52, 232));
+    }
+
+    #[test]
+    fn learnsets_beyond_starters() {
+        // Thrash, then Bite at 20.
+        let gyarados = PokedexData::get_pokemon(130).unwrap();
+        let gyarados = gyarados.learnset();
+        assert_eq!(gyarados.default_moves(20), (37, 44, 0, 0));
+        assert!(gyarados.can_learn(85, 20)); // Thunderbolt by TM
+                                             // Astonish drops out once Vice Grip is learned at 21.
+        let mawile = PokedexData::get_pokemon(303).unwrap();
+        let mawile = mawile.learnset();
+        assert_eq!(mawile.default_moves(21), (313, 44, 230, 11));
+        assert!(mawile.egg.contains(&14)); // Swords Dance
+        let shedinja = PokedexData::get_pokemon(292).unwrap();
+        let shedinja = shedinja.learnset();
+        assert_eq!(shedinja.default_moves(25), (28, 154, 170, 180));
+
+        let mut mawile = Monster::from_dex(303);
+        mawile.set_level(21);
+        assert_eq!(mawile.default_moves_for_level(), (313, 44, 230, 11));
+    }
+
+    #[test]
+    fn learnsets_after_gen3() {
+        // Tackle, Withdraw, Absorb, then Razor Leaf at 13.
+        let turtwig = PokedexData::get_pokemon(387).unwrap();
+        assert_eq!(turtwig.learnset().default_moves(13), (33, 110, 71, 75));
+        // Wood Hammer, Splash, Scratch and Astonish at 1.
+        let mimikyu = PokedexData::get_pokemon(778).unwrap();
+        assert_eq!(mimikyu.learnset().default_moves(1), (452, 150, 10, 310));
+        // Leafage isn't in the move data, leaving Tackle and Growl.
+        let rowlet = PokedexData::get_pokemon(722).unwrap();
+        assert_eq!(rowlet.learnset().default_moves(4), (33, 45, 0, 0));
+        assert_ne!(Monster::from_dex(809).moves, (0, 0, 0, 0));
+    }
+}
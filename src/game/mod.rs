@@ -1,11 +1,32 @@
+use data::{error::DataError, result, TypesEntry};
+use dex::Dex;
 use rand::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
+pub mod abilities;
+pub mod battle;
+pub mod builder;
+pub mod critical;
+pub mod damage;
+/// Deserialization structs for Pokemon data.
+pub mod data;
+pub mod dex;
+pub mod events;
+pub mod evolution;
+pub mod experience;
+pub mod items;
+pub mod lcrng;
+pub mod learnset;
 pub mod monster;
 pub mod moves;
+pub mod overlay;
+pub mod personality;
 pub mod pokedex;
-/// Deserialization structs for Pokemon data.
-pub mod data;
+pub mod replay;
+pub mod rng;
+pub mod species;
+pub mod training;
 
 #[repr(u8)]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Eq, Hash)]
@@ -30,9 +51,11 @@ pub enum ElementType {
     Fairy,
 }
 
-impl From<&str> for ElementType {
-    fn from(v: &str) -> Self {
-        match v {
+impl TryFrom<&str> for ElementType {
+    type Error = DataError;
+
+    fn try_from(v: &str) -> result::Result<Self> {
+        let element = match v {
             "Normal" => ElementType::Normal,
             "Fire" => ElementType::Fire,
             "Water" => ElementType::Water,
@@ -51,27 +74,148 @@ impl From<&str> for ElementType {
             "Dark" => ElementType::Dark,
             "Steel" => ElementType::Steel,
             "Fairy" => ElementType::Fairy,
-            _ => panic!("Unsupported element type."),
-        }
+            _ => return Err(DataError::invalid(format!("Unknown element type: {}.", v))),
+        };
+        Ok(element)
     }
 }
 
-pub const POKEMON_ELEMENTS: [&'static str; 18] = [
+pub const POKEMON_ELEMENTS: [&str; 18] = [
     "Normal", "Fire", "Water", "Electric", "Grass", "Ice", "Fighting", "Poison", "Ground",
     "Flying", "Psychic", "Bug", "Rock", "Ghost", "Dragon", "Dark", "Steel", "Fairy",
 ];
 
+/// Type effectiveness chart, indexed as `[attacker][defender]` in `ElementType` order.
+/// Values are stored in half-steps: 0 = immune, 1 = not very effective, 2 = neutral,
+/// 4 = super effective.
+#[rustfmt::skip]
+const TYPE_CHART: [[u8; 18]; 18] = [
+    //    Nor Fir Wat Ele Gra Ice Fig Poi Gro Fly Psy Bug Roc Gho Dra Dar Ste Fai
+    /* Normal   */ [2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 1, 0, 2, 2, 1, 2],
+    /* Fire     */ [2, 1, 1, 2, 4, 4, 2, 2, 2, 2, 2, 4, 1, 2, 1, 2, 4, 2],
+    /* Water    */ [2, 4, 1, 2, 1, 2, 2, 2, 4, 2, 2, 2, 4, 2, 1, 2, 2, 2],
+    /* Electric */ [2, 2, 4, 1, 1, 2, 2, 2, 0, 4, 2, 2, 2, 2, 1, 2, 2, 2],
+    /* Grass    */ [2, 1, 4, 2, 1, 2, 2, 1, 4, 1, 2, 1, 4, 2, 1, 2, 1, 2],
+    /* Ice      */ [2, 1, 1, 2, 4, 1, 2, 2, 4, 4, 2, 2, 2, 2, 4, 2, 1, 2],
+    /* Fighting */ [4, 2, 2, 2, 2, 4, 2, 1, 2, 1, 1, 1, 4, 0, 2, 4, 4, 1],
+    /* Poison   */ [2, 2, 2, 2, 4, 2, 2, 1, 1, 2, 2, 2, 1, 1, 2, 2, 0, 4],
+    /* Ground   */ [2, 4, 2, 4, 1, 2, 2, 4, 2, 0, 2, 1, 4, 2, 2, 2, 4, 2],
+    /* Flying   */ [2, 2, 2, 1, 4, 2, 4, 2, 2, 2, 2, 4, 1, 2, 2, 2, 1, 2],
+    /* Psychic  */ [2, 2, 2, 2, 2, 2, 4, 4, 2, 2, 1, 2, 2, 2, 2, 0, 1, 2],
+    /* Bug      */ [2, 1, 2, 2, 4, 2, 1, 1, 2, 1, 4, 2, 2, 1, 2, 4, 1, 1],
+    /* Rock     */ [2, 4, 2, 2, 2, 4, 1, 2, 1, 4, 2, 4, 2, 2, 2, 2, 1, 2],
+    /* Ghost    */ [0, 2, 2, 2, 2, 2, 2, 2, 2, 2, 4, 2, 2, 4, 2, 1, 2, 2],
+    /* Dragon   */ [2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 4, 2, 1, 0],
+    /* Dark     */ [2, 2, 2, 2, 2, 2, 1, 2, 2, 2, 4, 2, 2, 4, 2, 1, 2, 1],
+    /* Steel    */ [2, 1, 1, 1, 2, 4, 2, 2, 2, 2, 2, 2, 4, 2, 2, 2, 1, 4],
+    /* Fairy    */ [2, 1, 2, 2, 2, 2, 4, 1, 2, 2, 2, 2, 2, 2, 4, 4, 1, 2],
+];
+
 impl ElementType {
-    pub fn generate() -> (ElementType, ElementType) {
-        let mut rng = thread_rng();
-        let i1: usize = rng.gen_range(0..POKEMON_ELEMENTS.len());
-        let i2: usize = rng.gen_range(0..POKEMON_ELEMENTS.len());
-        (
-            ElementType::from(POKEMON_ELEMENTS[i1]),
-            ElementType::from(POKEMON_ELEMENTS[i2]),
-        )
+    /// Get the multiplier of a single attacking element against a single defending element.
+    /// Returns one of 0, 0.5, 1 or 2.
+    pub fn against(&self, defender: &ElementType) -> f32 {
+        TYPE_CHART[self.clone() as usize][defender.clone() as usize] as f32 / 2f32
+    }
+    /// Get the multiplier of an attacking element against a defending type pairing.
+    /// Duplicate elements are treated as a single-type defender, matching `Monster::elements`.
+    /// Returns one of 0, 0.25, 0.5, 1, 2 or 4.
+    pub fn effectiveness(attacker: &ElementType, defender: &(ElementType, ElementType)) -> f32 {
+        let first = attacker.against(&defender.0);
+        if defender.0 == defender.1 {
+            first
+        } else {
+            first * attacker.against(&defender.1)
+        }
+    }
+    /// Get the localized names of this element.
+    pub fn get_names(&self) -> Option<TypesEntry> {
+        Dex::global().ok()?.get_type_names(self).cloned()
+    }
+    pub fn from_entries(
+        entries: &[TypesEntry],
+    ) -> result::Result<HashMap<ElementType, TypesEntry>> {
+        let mut map: HashMap<ElementType, TypesEntry> = HashMap::new();
+        for obj in entries.iter() {
+            map.insert(ElementType::try_from(obj.english.as_str())?, obj.clone());
+        }
+        Ok(map)
+    }
+    pub fn generate<R: Rng + ?Sized>(rng: &mut R) -> (ElementType, ElementType) {
+        let mut pick = || {
+            let name = POKEMON_ELEMENTS[rng.gen_range(0..POKEMON_ELEMENTS.len())];
+            ElementType::try_from(name).expect("POKEMON_ELEMENTS only holds valid names.")
+        };
+        (pick(), pick())
     }
 }
 
 unsafe impl Send for ElementType {}
 unsafe impl Sync for ElementType {}
+
+#[cfg(test)]
+mod tests {
+    use super::ElementType;
+
+    #[test]
+    fn test_effectiveness() {
+        let dual = |a: ElementType, b: ElementType| (a, b);
+        // Single-type monsters use duplicated elements and must not be squared.
+        assert_eq!(
+            ElementType::effectiveness(
+                &ElementType::Water,
+                &dual(ElementType::Fire, ElementType::Fire)
+            ),
+            2.0
+        );
+        assert_eq!(
+            ElementType::effectiveness(
+                &ElementType::Ice,
+                &dual(ElementType::Dragon, ElementType::Ground)
+            ),
+            4.0
+        );
+        assert_eq!(
+            ElementType::effectiveness(
+                &ElementType::Fire,
+                &dual(ElementType::Water, ElementType::Dragon)
+            ),
+            0.25
+        );
+        assert_eq!(
+            ElementType::effectiveness(
+                &ElementType::Electric,
+                &dual(ElementType::Water, ElementType::Ground)
+            ),
+            0.0
+        );
+        assert_eq!(
+            ElementType::effectiveness(
+                &ElementType::Fighting,
+                &dual(ElementType::Normal, ElementType::Flying)
+            ),
+            1.0
+        );
+        assert_eq!(
+            ElementType::effectiveness(
+                &ElementType::Normal,
+                &dual(ElementType::Ghost, ElementType::Ghost)
+            ),
+            0.0
+        );
+        assert_eq!(
+            ElementType::effectiveness(
+                &ElementType::Grass,
+                &dual(ElementType::Grass, ElementType::Poison)
+            ),
+            0.25
+        );
+    }
+
+    #[test]
+    fn test_element_names() {
+        let names = ElementType::Fire.get_names().unwrap();
+        assert_eq!(names.english, "Fire");
+        assert_eq!(names.japanese.as_deref(), Some("ほのお"));
+    }
+}
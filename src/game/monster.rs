@@ -1,17 +1,24 @@
 use super::{
-    moves::{MoveCategory, PokemonMove, POKEMON_MOVES},
-    pokedex::PokedexData,
+    abilities::{self, AbilityHooks, PokemonAbility},
+    damage::DamageCalc,
+    data::{error::DataError, result as data_result},
+    dex::Dex,
+    evolution::{Evolution, EvolutionContext},
+    experience::MAX_LEVEL,
+    items::PokemonItem,
+    moves::PokemonMove,
+    personality::{OriginalTrainer, Personality},
+    species::{GenderRatio, GrowthRate},
     ElementType,
 };
 use enumflags2::{bitflags, BitFlags};
 use rand::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use uuid::Uuid;
 
 #[bitflags]
 #[repr(u16)]
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum MonsterStatus {
     BRN,
     FRZ,
@@ -25,6 +32,31 @@ pub enum MonsterStatus {
     DRSY,
     ENC,
     FLN,
+    /// Focus Energy's raised critical hit stage.
+    FCS,
+    /// Badly poisoned, taking more damage each turn.
+    TOX,
+}
+
+/// How much paralysis lowers Speed.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Eq, Hash, Default)]
+pub enum ParalysisMechanic {
+    /// Gen3 to Gen6: paralysis quarters Speed.
+    Gen3To6,
+    /// Gen7+: paralysis halves Speed.
+    #[default]
+    Gen7Plus,
+}
+
+impl ParalysisMechanic {
+    /// The divisor applied to a paralyzed monster's Speed.
+    pub fn speed_divisor(&self) -> u16 {
+        match self {
+            ParalysisMechanic::Gen3To6 => 4,
+            ParalysisMechanic::Gen7Plus => 2,
+        }
+    }
 }
 
 pub trait MonsterStatType {
@@ -71,21 +103,16 @@ impl MonsterStages {
     }
     pub fn apply_stages(&mut self, stat: MonsterAttribute, stages: i8) {
         if let Some(current) = self.internal.get(&stat) {
-            let new = {
-                let temp = *current + stages;
-                if temp > 6 {
-                    6
-                } else if temp < -6 {
-                    -6
-                } else {
-                    temp
-                }
-            };
+            let new = (*current + stages).clamp(-6, 6);
             self.internal.insert(stat.clone(), new);
         } else {
             panic!("Invalid attribute.")
         }
     }
+    /// The current stage of a battle stat.
+    pub fn get(&self, stat: &MonsterAttribute) -> i8 {
+        self.internal.get(stat).copied().unwrap_or_default()
+    }
     pub fn reset(&mut self) {
         self.internal = HashMap::from_iter([
             (MonsterAttribute::ATT, 0),
@@ -159,6 +186,26 @@ impl MonsterStages {
             panic!("Attribute not mapped.")
         }
     }
+    /// Apply the current stage of a battle stat to a computed stat value.
+    /// Uses the games' integer fractions (2/2 through 8/2 and 2/8), truncating the result.
+    pub fn apply_to(&self, stat: MonsterAttribute, value: u16) -> u16 {
+        if let Some(val) = self.internal.get(&stat) {
+            Self::apply_stage(*val, value)
+        } else {
+            panic!("Attribute not mapped.")
+        }
+    }
+    /// Apply a stage to a computed stat value; see `apply_to`.
+    pub fn apply_stage(stage: i8, value: u16) -> u16 {
+        let stage = stage.clamp(-6, 6) as i32;
+        let value = value as u32;
+        let res = if stage >= 0 {
+            value * (2 + stage) as u32 / 2
+        } else {
+            value * 2 / (2 - stage) as u32
+        };
+        res as u16
+    }
 }
 
 impl From<&str> for MonsterAttribute {
@@ -176,7 +223,7 @@ impl From<&str> for MonsterAttribute {
 }
 
 #[allow(dead_code)]
-const POKEMON_ATTRIBUTES: [&'static str; 6] = [
+const POKEMON_ATTRIBUTES: [&str; 6] = [
     "HP",
     "Attack",
     "Defense",
@@ -274,58 +321,111 @@ impl From<&str> for MonsterNature {
     }
 }
 
-const POKEMON_NATURES: [&'static str; 25] = [
+const POKEMON_NATURES: [&str; 25] = [
     "Adamant", "Bashful", "Bold", "Brave", "Calm", "Careful", "Docile", "Gentle", "Hardy", "Hasty",
     "Impish", "Jolly", "Lax", "Lonely", "Mild", "Modest", "Naive", "Naughty", "Quiet", "Quirky",
     "Rash", "Relaxed", "Sassy", "Serious", "Timid",
 ];
 
 impl MonsterNature {
-    pub fn generate() -> Self {
-        let mut rng = thread_rng();
+    pub fn generate<R: Rng + ?Sized>(rng: &mut R) -> Self {
         let i: usize = rng.gen_range(0..POKEMON_NATURES.len());
         MonsterNature::from(POKEMON_NATURES[i])
     }
+    /// The stats raised and lowered by 10%, or `None` for neutral natures.
+    pub fn modifiers(&self) -> Option<(MonsterAttribute, MonsterAttribute)> {
+        NATURE_MODIFIERS.get(self).cloned().flatten()
+    }
+    /// The stat raised by 10%, or `None` for neutral natures.
+    pub fn increased(&self) -> Option<MonsterAttribute> {
+        self.modifiers().map(|(up, _)| up)
+    }
+    /// The stat lowered by 10%, or `None` for neutral natures.
+    pub fn decreased(&self) -> Option<MonsterAttribute> {
+        self.modifiers().map(|(_, down)| down)
+    }
+    /// Whether this nature leaves every stat unchanged.
+    pub fn is_neutral(&self) -> bool {
+        self.increased().is_none()
+    }
+    /// The flavor this nature likes, or `None` for neutral natures.
+    pub fn likes(&self) -> Option<Flavor> {
+        self.increased().and_then(|attr| Flavor::for_stat(&attr))
+    }
+    /// The flavor this nature dislikes, or `None` for neutral natures.
+    pub fn dislikes(&self) -> Option<Flavor> {
+        self.decreased().and_then(|attr| Flavor::for_stat(&attr))
+    }
+}
+
+/// Berry and Pokéblock flavors, each tied to the stat natures raise or lower.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum Flavor {
+    Spicy,
+    Dry,
+    Sweet,
+    Bitter,
+    Sour,
+}
+
+impl Flavor {
+    /// The flavor tied to a stat. HP, accuracy and evasion have none.
+    pub fn for_stat(attr: &MonsterAttribute) -> Option<Self> {
+        match attr {
+            MonsterAttribute::ATT => Some(Flavor::Spicy),
+            MonsterAttribute::SpATT => Some(Flavor::Dry),
+            MonsterAttribute::SPD => Some(Flavor::Sweet),
+            MonsterAttribute::SpDEF => Some(Flavor::Bitter),
+            MonsterAttribute::DEF => Some(Flavor::Sour),
+            _ => None,
+        }
+    }
+    /// The stat tied to this flavor.
+    pub fn stat(&self) -> MonsterAttribute {
+        match self {
+            Flavor::Spicy => MonsterAttribute::ATT,
+            Flavor::Dry => MonsterAttribute::SpATT,
+            Flavor::Sweet => MonsterAttribute::SPD,
+            Flavor::Bitter => MonsterAttribute::SpDEF,
+            Flavor::Sour => MonsterAttribute::DEF,
+        }
+    }
 }
 
 lazy_static! {
-    static ref NATURE_ARR: Vec<(&'static str, MonsterAttribute, MonsterAttribute)> = vec![
-        ("Adamant", MonsterAttribute::ATT, MonsterAttribute::SpATT),
-        ("Bashful", MonsterAttribute::SpATT, MonsterAttribute::SpATT),
-        ("Bold", MonsterAttribute::DEF, MonsterAttribute::ATT),
-        ("Brave", MonsterAttribute::ATT, MonsterAttribute::SPD),
-        ("Calm", MonsterAttribute::SpDEF, MonsterAttribute::ATT),
-        ("Careful", MonsterAttribute::SpDEF, MonsterAttribute::SpATT),
-        ("Docile", MonsterAttribute::DEF, MonsterAttribute::DEF),
-        ("Gentle", MonsterAttribute::SpDEF, MonsterAttribute::DEF),
-        ("Hardy", MonsterAttribute::ATT, MonsterAttribute::ATT),
-        ("Hasty", MonsterAttribute::SPD, MonsterAttribute::DEF),
-        ("Impish", MonsterAttribute::DEF, MonsterAttribute::SpATT),
-        ("Jolly", MonsterAttribute::SPD, MonsterAttribute::SpATT),
-        ("Lax", MonsterAttribute::DEF, MonsterAttribute::SpDEF),
-        ("Lonely", MonsterAttribute::ATT, MonsterAttribute::DEF),
-        ("Mild", MonsterAttribute::SpATT, MonsterAttribute::DEF),
-        ("Modest", MonsterAttribute::SpATT, MonsterAttribute::ATT),
-        ("Naive", MonsterAttribute::SPD, MonsterAttribute::SpDEF),
-        ("Naughty", MonsterAttribute::ATT, MonsterAttribute::SpDEF),
-        ("Quiet", MonsterAttribute::SpATT, MonsterAttribute::SPD),
-        ("Quirky", MonsterAttribute::SpDEF, MonsterAttribute::SpDEF),
-        ("Rash", MonsterAttribute::SpATT, MonsterAttribute::SpDEF),
-        ("Relaxed", MonsterAttribute::DEF, MonsterAttribute::SPD),
-        ("Sassy", MonsterAttribute::SpDEF, MonsterAttribute::SPD),
-        ("Serious", MonsterAttribute::SPD, MonsterAttribute::SPD),
-        ("Timid", MonsterAttribute::SPD, MonsterAttribute::ATT),
+    /// Each nature's raised and lowered stat, or `None` for neutral natures.
+    static ref NATURE_ARR: Vec<(&'static str, Option<(MonsterAttribute, MonsterAttribute)>)> = vec![
+        ("Adamant", Some((MonsterAttribute::ATT, MonsterAttribute::SpATT))),
+        ("Bashful", None),
+        ("Bold", Some((MonsterAttribute::DEF, MonsterAttribute::ATT))),
+        ("Brave", Some((MonsterAttribute::ATT, MonsterAttribute::SPD))),
+        ("Calm", Some((MonsterAttribute::SpDEF, MonsterAttribute::ATT))),
+        ("Careful", Some((MonsterAttribute::SpDEF, MonsterAttribute::SpATT))),
+        ("Docile", None),
+        ("Gentle", Some((MonsterAttribute::SpDEF, MonsterAttribute::DEF))),
+        ("Hardy", None),
+        ("Hasty", Some((MonsterAttribute::SPD, MonsterAttribute::DEF))),
+        ("Impish", Some((MonsterAttribute::DEF, MonsterAttribute::SpATT))),
+        ("Jolly", Some((MonsterAttribute::SPD, MonsterAttribute::SpATT))),
+        ("Lax", Some((MonsterAttribute::DEF, MonsterAttribute::SpDEF))),
+        ("Lonely", Some((MonsterAttribute::ATT, MonsterAttribute::DEF))),
+        ("Mild", Some((MonsterAttribute::SpATT, MonsterAttribute::DEF))),
+        ("Modest", Some((MonsterAttribute::SpATT, MonsterAttribute::ATT))),
+        ("Naive", Some((MonsterAttribute::SPD, MonsterAttribute::SpDEF))),
+        ("Naughty", Some((MonsterAttribute::ATT, MonsterAttribute::SpDEF))),
+        ("Quiet", Some((MonsterAttribute::SpATT, MonsterAttribute::SPD))),
+        ("Quirky", None),
+        ("Rash", Some((MonsterAttribute::SpATT, MonsterAttribute::SpDEF))),
+        ("Relaxed", Some((MonsterAttribute::DEF, MonsterAttribute::SPD))),
+        ("Sassy", Some((MonsterAttribute::SpDEF, MonsterAttribute::SPD))),
+        ("Serious", None),
+        ("Timid", Some((MonsterAttribute::SPD, MonsterAttribute::ATT))),
     ];
-    pub static ref NATURE_MODIFERS: HashMap<MonsterNature, (MonsterAttribute, MonsterAttribute)> = {
-        let mut map: HashMap<MonsterNature, (MonsterAttribute, MonsterAttribute)> = HashMap::new();
-        for entry in (*NATURE_ARR).iter() {
-            map.insert(
-                MonsterNature::from(entry.0),
-                (entry.1.clone(), entry.2.clone()),
-            );
-        }
-        map
-    };
+    static ref NATURE_MODIFIERS: HashMap<MonsterNature, Option<(MonsterAttribute, MonsterAttribute)>> =
+        NATURE_ARR
+            .iter()
+            .map(|(name, modifiers)| (MonsterNature::from(*name), modifiers.clone()))
+            .collect();
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -342,10 +442,13 @@ unsafe impl Sync for MonsterAttribute {}
 unsafe impl Sync for MonsterStats {}
 
 pub mod error {
+    use crate::game::data::error::DataError;
+
     #[derive(Debug)]
     pub enum MonsterError {
         StatParseError,
         MonsterParseError(String),
+        Data(DataError),
     }
 
     impl std::fmt::Display for MonsterError {
@@ -353,18 +456,31 @@ pub mod error {
             let msg = match self {
                 MonsterError::StatParseError => "Stat parse error.".to_string(),
                 MonsterError::MonsterParseError(data) => data.clone(),
+                MonsterError::Data(e) => e.to_string(),
             };
             write!(f, "{}", msg)
         }
     }
 
     impl std::error::Error for MonsterError {}
+
+    impl From<DataError> for MonsterError {
+        fn from(e: DataError) -> Self {
+            MonsterError::Data(e)
+        }
+    }
 }
 
 pub mod result {
     pub type Result<T> = std::result::Result<T, super::error::MonsterError>;
 }
 
+impl Default for MonsterStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl MonsterStats {
     pub fn new() -> Self {
         Self { internal: [0; 6] }
@@ -372,39 +488,38 @@ impl MonsterStats {
     /**
      * Generate a random set of values.
      * IVs 0-31
-     * EVs 0-255 - Capped at 510 total
+     * EVs 0-252 - Capped at 510 total, see `random_ev`
      */
-    pub fn random(max: u8, min: u8) -> Self {
-        let mut rng = thread_rng();
+    pub fn random<R: Rng + ?Sized>(rng: &mut R, max: u8, min: u8) -> Self {
         let mut internal: [u8; 6] = [0u8; 6];
-        for i in 0..6usize {
-            internal[i] = rng.gen_range(min..=max);
+        for val in internal.iter_mut() {
+            *val = rng.gen_range(min..=max);
         }
         internal.reverse();
         Self { internal }
     }
-    pub fn random_iv() -> Self {
-        Self::random(31, 0)
+    pub fn random_iv<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Self::random(rng, 31, 0)
     }
-    pub fn random_ev() -> Self {
-        let mut temp = Self::random(252, 0);
-        let mut total: u16 = 510;
-        for i in 0..6u8 {
-            let ix = MonsterAttribute::from(i);
-            let val = temp.get(ix.clone());
-            if total - val as u16 <= total {
-                total -= val as u16;
-            } else {
-                if total > 0 {
-                    temp.set(ix, val - total as u8);
-                    total = 0;
-                } else {
-                    temp.set(ix, 0);
-                }
-            }
+    /// Generate a random legal EV spread: at most 252 in a stat and 510 in total.
+    pub fn random_ev<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let mut temp = Self::new();
+        let mut remaining = MAX_EV_TOTAL;
+        let mut order: [u8; 6] = [0, 1, 2, 3, 4, 5];
+        // Fill stats in a random order so the cap doesn't favour the first ones.
+        order.shuffle(rng);
+        for i in order.iter() {
+            let max = remaining.min(MAX_EV as u16) as u8;
+            let val = rng.gen_range(0..=max);
+            temp.set(MonsterAttribute::from(*i), val);
+            remaining -= val as u16;
         }
         temp
     }
+    /// The sum of all six values.
+    pub fn total(&self) -> u16 {
+        self.internal.iter().map(|val| *val as u16).sum()
+    }
     pub fn get(&self, attribute: MonsterAttribute) -> u8 {
         self.internal[attribute as usize]
     }
@@ -426,6 +541,18 @@ impl MonsterStats {
     }
 }
 
+/// A monster's computed stats, indexed like `MonsterStats`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ComputedStats {
+    pub internal: [u16; 6],
+}
+
+impl ComputedStats {
+    pub fn get(&self, attribute: MonsterAttribute) -> u16 {
+        self.internal[attribute as usize]
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub enum MonsterGender {
     None,
@@ -434,19 +561,27 @@ pub enum MonsterGender {
 }
 
 impl MonsterGender {
-    pub fn generate() -> Self {
-        let mut rng = thread_rng();
-        if rng.gen::<bool>() {
-            MonsterGender::Male
-        } else {
-            MonsterGender::Female
+    pub fn generate<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Self::generate_for(rng, &GenderRatio::default())
+    }
+    /// Generate a gender following a species' gender ratio.
+    pub fn generate_for<R: Rng + ?Sized>(rng: &mut R, ratio: &GenderRatio) -> Self {
+        match ratio.is_female(rng.gen::<u8>()) {
+            Some(true) => MonsterGender::Female,
+            Some(false) => MonsterGender::Male,
+            None => MonsterGender::None,
         }
     }
 }
 
 /// The main Monster structure.
 /// Each instance represents a generated Monster.
+///
+/// Stats are cached in `stats`. After changing `level`, `base_stats`, `iv`, `ev` or
+/// `nature` directly, call `recompute_stats`, or use a setter like `set_level` that
+/// does it for you. The cache is rebuilt when a monster is deserialized.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(remote = "Self")]
 pub struct Monster {
     /// This monster's nickname.
     pub nickname: Option<String>,
@@ -456,88 +591,368 @@ pub struct Monster {
     pub pid: usize,
     /// The generated UUID for this specific monster.
     pub uuid: String,
-    /// This monster's level.
+    /// This monster's Gen3 personality value, which determines its nature,
+    /// gender, ability slot and shininess.
+    #[serde(default)]
+    pub personality: Personality,
+    /// The trainer that originally obtained this monster.
+    #[serde(default)]
+    pub trainer: OriginalTrainer,
+    /// This monster's level. See `set_level`.
     pub level: u8,
     /// This monster's current HP.
     pub hp: u16,
-    /// This monster's current XP.
-    pub xp: u16,
-    /// This monster's required XP for level up.
-    pub next_xp: u16,
+    /// This monster's total XP.
+    pub xp: u32,
+    /// The total XP this monster needs to reach its next level.
+    pub next_xp: u32,
     /// This monster's type pairing.
     /// Duplicate elements are used for a single-type monster.
     pub elements: (ElementType, ElementType),
     /// This monster's gender.
     pub gender: MonsterGender,
-    /// This monster's nature.
+    /// This monster's nature. See `set_nature`.
     pub nature: MonsterNature,
     /// This monster's moveset.
     /// Each value represents a move ID, with 0 being no move.
     pub moves: (u16, u16, u16, u16),
     /// This monster's currently held item.
     pub held_item: Option<u16>,
-    /// This monster's base stats.
+    /// This monster's ability ID, if its species has ability data.
+    #[serde(default)]
+    pub ability: Option<u16>,
+    /// This monster's base stats. Call `recompute_stats` after changing them.
     pub base_stats: MonsterStats,
-    /// This monster's generated IVs.
+    /// This monster's generated IVs. See `set_ivs`.
     pub iv: MonsterStats,
-    /// This monster's EVs.
+    /// This monster's EVs. See `set_evs`.
     pub ev: MonsterStats,
+    /// This monster's stats, computed from the values above.
+    /// Not serialized, since it is recomputed on load.
+    #[serde(skip)]
+    pub stats: ComputedStats,
+    /// This monster's friendship towards its trainer.
+    #[serde(default = "default_friendship")]
+    pub friendship: u8,
+    /// Whether this monster has, or has had, Pokérus, doubling the EVs it gains.
+    #[serde(default)]
+    pub pokerus: bool,
     /// This monster's combat stat modifiers.
     pub combat_mods: MonsterStages,
     /// This monster's combat status.
     pub in_combat: bool,
     /// This monster's combat status flags.
     pub combat_status: BitFlags<MonsterStatus>,
+    /// The turns left asleep, or the turns spent badly poisoned.
+    #[serde(default)]
+    pub status_turns: u8,
+}
+
+/// The friendship of a newly generated monster.
+pub const BASE_FRIENDSHIP: u8 = 70;
+
+/// The highest value of a single IV.
+pub const MAX_IV: u8 = 31;
+
+/// The most EVs a monster can have in a single stat.
+pub const MAX_EV: u8 = 252;
+
+/// The most EVs a monster can have across all stats.
+pub const MAX_EV_TOTAL: u16 = 510;
+
+fn default_friendship() -> u8 {
+    BASE_FRIENDSHIP
+}
+
+impl Serialize for Monster {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Monster::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Monster {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut mon = Monster::deserialize(deserializer)?;
+        mon.stats = mon.computed_stats();
+        Ok(mon)
+    }
 }
 
 unsafe impl Send for Monster {}
 unsafe impl Sync for Monster {}
 
+impl Default for Monster {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Monster {
+    /// Create a monster from its species ID.
+    /// Panics if the species data cannot be loaded; see `try_from_dex`.
     pub fn from_dex(id: usize) -> Self {
-        let data = PokedexData::get_pokemon(id).unwrap();
-        let mut mon = Self::new();
+        Self::try_from_dex(id).unwrap()
+    }
+    /// Create a monster from its species ID, failing for unknown species.
+    pub fn try_from_dex(id: usize) -> data_result::Result<Self> {
+        Self::try_from_dex_in(Dex::global()?, id)
+    }
+    /// Create a monster from a species ID in the given dex.
+    pub fn try_from_dex_in(dex: &Dex, id: usize) -> data_result::Result<Self> {
+        Self::try_from_dex_with(dex, id, &mut thread_rng())
+    }
+    /// Create a monster from a species ID in the given dex, drawing every random
+    /// value from `rng` so the same seed always generates the same monster.
+    /// Species without learnset data start with no moves.
+    pub fn try_from_dex_with<R: Rng + ?Sized>(
+        dex: &Dex,
+        id: usize,
+        rng: &mut R,
+    ) -> data_result::Result<Self> {
+        let data = dex.get_species(id)?;
+        let mut mon = Self::new_with(rng);
         mon.pid = data.id;
         mon.base_name = data.name.clone();
         mon.base_stats = data.base.clone();
         mon.elements = data.element.clone();
-        mon
+        mon.moves = data.learnset.default_moves(mon.level);
+        mon.ability = mon.personality.ability(&data.abilities);
+        if let Some(info) = &data.info {
+            mon.gender = mon.personality.gender(&info.gender_ratio);
+        }
+        let growth = mon.growth_rate_in(dex);
+        mon.xp = growth.exp_for_level(mon.level);
+        mon.next_xp = growth.exp_for_level(mon.level + 1);
+        mon.recompute_stats();
+        mon.hp = mon.max_hp();
+        Ok(mon)
     }
-    pub fn max_hp(&self) -> u16 {
-        if self.pid == 292 {
-            1
-        } else {
-            self.get_stat(MonsterAttribute::HP)
+    /// The evolutions this monster qualifies for in `ctx`.
+    pub fn pending_evolutions(&self, ctx: &EvolutionContext) -> Vec<Evolution> {
+        match Dex::global() {
+            Ok(dex) => self.pending_evolutions_in(dex, ctx),
+            Err(_) => Vec::new(),
         }
     }
-    pub fn get_stat(&self, attr: MonsterAttribute) -> u16 {
-        let base = self.base_stats.get(attr.clone()) as f32;
-        let iv = self.iv.get(attr.clone()) as f32;
-        let ev = (self.ev.get(attr.clone()) as f32 * 0.25f32).floor();
-        let level = self.level as f32;
-        match attr {
-            MonsterAttribute::HP => {
-                let res = (0.01f32 * (2f32 * base + iv + ev) * level).floor() + level + 10f32;
-                res.round() as u16
+    /// The evolutions this monster qualifies for in `ctx`, using the given dex.
+    pub fn pending_evolutions_in(&self, dex: &Dex, ctx: &EvolutionContext) -> Vec<Evolution> {
+        dex.get_evolutions(self.pid)
+            .iter()
+            .filter(|evo| evo.is_satisfied(self, ctx))
+            .cloned()
+            .collect()
+    }
+    /// Evolve this monster into `target`, one of its species' evolutions.
+    pub fn evolve(&mut self, target: usize) -> data_result::Result<()> {
+        self.evolve_in(Dex::global()?, target)
+    }
+    /// Evolve this monster into `target` using the given dex.
+    /// Species data is replaced while IVs, EVs, nature, nickname and UUID are kept,
+    /// and the ability is replaced by the one in the same slot.
+    /// Stats are recomputed and damage taken carries over to the new maximum HP.
+    pub fn evolve_in(&mut self, dex: &Dex, target: usize) -> data_result::Result<()> {
+        let evolution = dex
+            .get_evolutions(self.pid)
+            .iter()
+            .find(|evo| evo.to == target)
+            .ok_or(DataError::UnknownEvolution {
+                from: self.pid,
+                to: target,
+            })?;
+        let data = dex.get_species(target)?;
+        if evolution.consumes_held_item() {
+            self.held_item = None;
+        }
+        // Abilities keep their slot, so the second ability evolves into the second ability.
+        if let Ok(current) = dex.get_species(self.pid) {
+            let hidden = self.ability.is_some() && self.ability == current.abilities.hidden;
+            let slot = current
+                .abilities
+                .regular
+                .iter()
+                .position(|id| Some(*id) == self.ability);
+            let evolved = if hidden {
+                data.abilities.hidden
+            } else {
+                slot.and_then(|i| {
+                    data.abilities
+                        .regular
+                        .get(i)
+                        .or(data.abilities.regular.first())
+                })
+                .copied()
+            };
+            if evolved.is_some() {
+                self.ability = evolved;
             }
+        }
+        self.pid = data.id;
+        self.base_name = data.name.clone();
+        self.base_stats = data.base.clone();
+        self.elements = data.element.clone();
+        self.recompute_stats();
+        Ok(())
+    }
+    /// The four most recent level-up moves for this monster's species and level.
+    /// Returns empty slots when the species has no learnset data.
+    pub fn default_moves_for_level(&self) -> (u16, u16, u16, u16) {
+        match Dex::global() {
+            Ok(dex) => self.default_moves_for_level_in(dex),
+            Err(_) => (0, 0, 0, 0),
+        }
+    }
+    /// The four most recent level-up moves for this monster, using the given dex.
+    pub fn default_moves_for_level_in(&self, dex: &Dex) -> (u16, u16, u16, u16) {
+        match dex.get_species(self.pid) {
+            Ok(data) => data.learnset.default_moves(self.level),
+            Err(_) => (0, 0, 0, 0),
+        }
+    }
+    pub fn max_hp(&self) -> u16 {
+        self.stats.get(MonsterAttribute::HP)
+    }
+    /// One of this monster's cached stats; see `recompute_stats`.
+    pub fn get_stat(&self, attr: MonsterAttribute) -> u16 {
+        self.stats.get(attr)
+    }
+    /// Calculate a stat with the games' integer formulas, flooring after every step.
+    pub fn compute_stat(&self, attr: MonsterAttribute) -> u16 {
+        let base = self.base_stats.get(attr.clone()) as u32;
+        let iv = self.iv.get(attr.clone()) as u32;
+        let ev = self.ev.get(attr.clone()) as u32 / 4;
+        let level = self.level as u32;
+        let core = (2 * base + iv + ev) * level / 100;
+        let res = match attr {
+            // Shedinja always has exactly 1 HP.
+            MonsterAttribute::HP if self.pid == 292 => 1,
+            MonsterAttribute::HP => core + level + 10,
             _ => {
-                let nature_bonus: f32 = {
-                    let bn = (*NATURE_MODIFERS).get(&self.nature).unwrap().clone();
-                    if bn.0 == attr {
-                        1.1f32
-                    } else if bn.1 == attr {
-                        0.9f32
-                    } else {
-                        1f32
-                    }
+                let percent = if self.nature.increased().as_ref() == Some(&attr) {
+                    110
+                } else if self.nature.decreased().as_ref() == Some(&attr) {
+                    90
+                } else {
+                    100
                 };
-                let res =
-                    ((0.01f32 * (2f32 * base + iv + ev) * level).floor() + 5f32) * nature_bonus;
-                res.round() as u16
+                (core + 5) * percent / 100
             }
+        };
+        res as u16
+    }
+    /// Recompute the cached stats after changing level, base stats, IVs, EVs or nature.
+    /// Damage taken carries over to the new maximum HP.
+    pub fn recompute_stats(&mut self) {
+        let damage = self.max_hp().saturating_sub(self.hp);
+        self.stats = self.computed_stats();
+        self.hp = self.max_hp().saturating_sub(damage);
+    }
+    fn computed_stats(&self) -> ComputedStats {
+        let mut stats = ComputedStats::default();
+        for i in 0..6u8 {
+            let attr = MonsterAttribute::from(i);
+            stats.internal[i as usize] = self.compute_stat(attr);
+        }
+        stats
+    }
+    /// Set this monster's nature, recomputing its stats.
+    pub fn set_nature(&mut self, nature: MonsterNature) {
+        self.nature = nature;
+        self.recompute_stats();
+    }
+    /// Set this monster's IVs, recomputing its stats.
+    pub fn set_ivs(&mut self, iv: MonsterStats) {
+        self.iv = iv;
+        self.recompute_stats();
+    }
+    /// Set this monster's EVs, recomputing its stats.
+    /// Unlike `add_evs`, the spread is not checked against the EV caps.
+    pub fn set_evs(&mut self, ev: MonsterStats) {
+        self.ev = ev;
+        self.recompute_stats();
+    }
+    /// Set this monster's level and total XP to the start of that level,
+    /// recomputing its stats.
+    pub fn set_level(&mut self, level: u8) {
+        match Dex::global() {
+            Ok(dex) => self.set_level_in(dex, level),
+            Err(_) => self.set_level_in(&Dex::new(), level),
+        }
+    }
+    /// Set this monster's level, using the growth rate from the given dex.
+    pub fn set_level_in(&mut self, dex: &Dex, level: u8) {
+        let growth = self.growth_rate_in(dex);
+        self.level = level.clamp(1, MAX_LEVEL);
+        self.xp = growth.exp_for_level(self.level);
+        self.next_xp = growth.exp_for_level((self.level + 1).min(MAX_LEVEL));
+        self.recompute_stats();
+    }
+    /// A battle stat after stat stages and this monster's ability.
+    pub fn get_battle_stat(&self, attr: MonsterAttribute) -> u16 {
+        let value = self
+            .combat_mods
+            .apply_to(attr.clone(), self.get_stat(attr.clone()));
+        self.ability_hooks().modify_stat(self, &attr, value)
+    }
+    /// A battle stat for a critical hit, which ignores the attacker's lowered stages
+    /// and the defender's raised stages.
+    pub fn get_critical_stat(&self, attr: MonsterAttribute, attacking: bool) -> u16 {
+        let stage = self.combat_mods.get(&attr);
+        let stage = if attacking {
+            stage.max(0)
+        } else {
+            stage.min(0)
+        };
+        let value = MonsterStages::apply_stage(stage, self.get_stat(attr.clone()));
+        self.ability_hooks().modify_stat(self, &attr, value)
+    }
+    /// The Speed used for turn order: Speed after its stat stage and ability,
+    /// divided by paralysis under the given mechanic.
+    pub fn effective_speed(&self, paralysis: ParalysisMechanic) -> u16 {
+        let speed = self.get_battle_stat(MonsterAttribute::SPD);
+        if self.combat_status.contains(MonsterStatus::PAR) {
+            speed / paralysis.speed_divisor()
+        } else {
+            speed
+        }
+    }
+    /// Resolve this monster's ability, if any.
+    pub fn get_ability(&self) -> Option<PokemonAbility> {
+        self.get_ability_in(Dex::global().ok()?).cloned()
+    }
+    /// Resolve this monster's ability in the given dex.
+    pub fn get_ability_in<'a>(&self, dex: &'a Dex) -> Option<&'a PokemonAbility> {
+        self.ability
+            .and_then(|id| dex.get_ability(id as usize).ok())
+    }
+    /// The battle hooks of this monster's ability.
+    pub fn ability_hooks(&self) -> &'static dyn AbilityHooks {
+        abilities::get_hooks(self.ability)
+    }
+    /// Resolve this monster's held item, if any.
+    pub fn get_held_item(&self) -> Option<PokemonItem> {
+        self.get_held_item_in(Dex::global().ok()?).cloned()
+    }
+    /// Resolve this monster's held item in the given dex.
+    pub fn get_held_item_in<'a>(&self, dex: &'a Dex) -> Option<&'a PokemonItem> {
+        self.held_item.and_then(|id| dex.get_item(id as usize).ok())
+    }
+    /// Set this monster's held item, rejecting IDs missing from the item data.
+    pub fn set_held_item(&mut self, item: Option<u16>) -> result::Result<()> {
+        self.set_held_item_in(Dex::global()?, item)
+    }
+    /// Set this monster's held item, rejecting IDs missing from the given dex.
+    pub fn set_held_item_in(&mut self, dex: &Dex, item: Option<u16>) -> result::Result<()> {
+        if let Some(id) = item {
+            dex.get_item(id as usize)?;
         }
+        self.held_item = item;
+        Ok(())
     }
     pub fn get_move(&self, index: usize) -> Option<PokemonMove> {
+        self.get_move_in(Dex::global().ok()?, index).cloned()
+    }
+    /// Resolve one of this monster's moves in the given dex.
+    pub fn get_move_in<'a>(&self, dex: &'a Dex, index: usize) -> Option<&'a PokemonMove> {
         let move_id: usize = match index {
             0 => self.moves.0,
             1 => self.moves.1,
@@ -548,116 +963,221 @@ impl Monster {
         if move_id == 0 {
             None
         } else {
-            if let Some(entry) = (*POKEMON_MOVES).get(&move_id) {
-                Some(entry.clone())
-            } else {
-                None
-            }
+            dex.get_move(move_id).ok()
         }
     }
-    pub fn did_hit(&self, move_acc: u8, target_evade: f32) -> bool {
-        let mut rng = thread_rng();
-        let comp: u8 = rng.gen_range(1..=100);
+    pub fn did_hit<R: Rng + ?Sized>(&self, rng: &mut R, move_acc: u8, target_evade: f32) -> bool {
+        self.hits(rng.gen_range(1..=100), move_acc, target_evade)
+    }
+    /// Whether a move hits for an accuracy roll from 1 to 100.
+    pub fn hits(&self, comp: u8, move_acc: u8, target_evade: f32) -> bool {
         let stage_mod = self.combat_mods.get_mod(MonsterAttribute::ACC);
         let fin = (move_acc as f32 * stage_mod * target_evade) as u8;
         comp <= fin
     }
-    pub fn deal_damage(
+    /// Roll the damage dealt by `mv` to `target`, or 0 for moves that deal no damage.
+    pub fn deal_damage<R: Rng + ?Sized>(
         &self,
-        move_power: u8,
-        move_category: MoveCategory,
-        target_def: u16,
+        rng: &mut R,
+        target: &Monster,
+        mv: &PokemonMove,
         crit: bool,
     ) -> u16 {
-        let mut rng = thread_rng();
-        let rnd = rng.gen_range(85..=100u8) as f32 / 100f32;
-        let att_power = match move_category {
-            MoveCategory::Physical => self.get_stat(MonsterAttribute::ATT),
-            _ => self.get_stat(MonsterAttribute::SpATT),
-        };
-        let att_def_mod = att_power / target_def;
-        let damage = (((2 * self.level as u16) / 5) + 2) * move_power as u16 * att_def_mod;
-        let damage = damage / 50 + 2;
-        let damage = (damage as f32 * {
-            if crit {
-                1.5f32
-            } else {
-                1.0f32
-            }
-        }) as u16;
-        let damage = (damage as f32 * rnd) as u16;
-        // TODO: Add type modifiers
-        damage
+        if let Some(calc) = DamageCalc::new(self, target, mv, crit) {
+            calc.roll(rng.gen_range(85..=100u8))
+        } else {
+            0
+        }
     }
     pub fn new() -> Self {
-        let iv = MonsterStats::random_iv();
+        Self::new_with(&mut thread_rng())
+    }
+    /// Create a monster drawing every random value, including its UUID, from `rng`.
+    pub fn new_with<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let iv = MonsterStats::random_iv(rng);
         let ev = MonsterStats::new();
         let stats = MonsterStats::new();
+        let uuid = uuid::Builder::from_bytes(rng.gen())
+            .set_variant(uuid::Variant::RFC4122)
+            .set_version(uuid::Version::Random)
+            .build();
+        let personality = Personality::generate(rng);
         let mut mon = Self {
             nickname: None,
             base_name: String::new(),
             pid: 0,
-            uuid: Uuid::new_v4().to_string(),
+            uuid: uuid.to_string(),
+            personality,
+            trainer: OriginalTrainer::generate(rng),
             level: 1,
             hp: 1,
             xp: 0,
-            next_xp: 50,
-            nature: MonsterNature::generate(),
-            gender: MonsterGender::generate(),
+            next_xp: GrowthRate::default().exp_for_level(2),
+            nature: personality.nature(),
+            gender: personality.gender(&GenderRatio::default()),
             moves: (33, 0, 0, 0),
             held_item: None,
-            elements: ElementType::generate(),
+            ability: None,
+            elements: ElementType::generate(rng),
             base_stats: stats,
             iv,
             ev,
+            stats: ComputedStats::default(),
             combat_mods: MonsterStages::new(),
             in_combat: false,
+            friendship: BASE_FRIENDSHIP,
+            pokerus: false,
             combat_status: BitFlags::empty(),
+            status_turns: 0,
         };
-        mon.hp = mon.get_stat(MonsterAttribute::HP);
+        mon.recompute_stats();
+        mon.hp = mon.max_hp();
         mon
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{Monster, MonsterAttribute, MonsterStatus};
+    use super::{Flavor, Monster, MonsterAttribute, MonsterNature, MonsterStats, MonsterStatus};
+    use crate::game::{
+        battle::{Action, Battle, BattleOutcome, BattleSide},
+        builder::MonsterBuilder,
+        dex::Dex,
+        events::BattleEvent,
+    };
+    use rand::{prelude::*, rngs::StdRng};
     use serde_json;
     #[test]
     fn test_stats() {
         let mut t = Monster::from_dex(1);
-        t.level = 20;
+        t.set_level(20);
         let mut t2 = Monster::from_dex(4);
         t2.combat_mods.apply_stages(MonsterAttribute::EV, 1);
-        t2.level = 20;
-        t.hp = t.max_hp();
-        t2.hp = t2.max_hp();
-        while t2.hp > 0 {
-            if let Some(move_a) = t.get_move(0) {
-                println!("[{} - {} / {}]", t2.base_name, t2.hp, t2.max_hp());
-                if t.did_hit(
-                    move_a.accuracy.unwrap(),
-                    t2.combat_mods.get_mod(MonsterAttribute::EV),
-                ) {
-                    let damage = t.deal_damage(
-                        move_a.power.unwrap(),
-                        move_a.category,
-                        t.get_stat(MonsterAttribute::DEF),
-                        false,
-                    );
-                    println!("{}'s {} hit!", t.base_name, move_a.name);
-                    if damage > t2.hp {
-                        break;
-                    }
-                    t2.hp -= damage;
-                } else {
-                    println!("{}'s attack missed!", t.base_name);
+        t2.set_level(20);
+        let mut battle = Battle::new(BattleSide::new(vec![t]), BattleSide::new(vec![t2]), 0);
+        while battle.outcome().is_none() {
+            let before = [battle.sides[0].active().hp, battle.sides[1].active().hp];
+            let events = battle
+                .take_turn([Action::Move(0), Action::Move(0)])
+                .unwrap();
+            for (side, hp) in battle.sides.iter().zip(before) {
+                let mon = side.active();
+                assert!(mon.hp <= hp && mon.hp <= mon.max_hp());
+            }
+            for event in events.iter() {
+                if let BattleEvent::Damage {
+                    amount,
+                    hp_before,
+                    hp_after,
+                    ..
+                } = event
+                {
+                    assert_eq!(*hp_after, hp_before.saturating_sub(*amount));
                 }
-            } else {
-                panic!("No move.")
             }
+            assert!(battle.turn < 100);
+        }
+        let winner = battle.winner().unwrap();
+        assert_eq!(battle.sides[1 - winner].active().hp, 0);
+        assert!(battle.sides[winner].active().hp > 0);
+        assert_eq!(
+            battle.events().last(),
+            Some(&BattleEvent::BattleEnded {
+                outcome: BattleOutcome::Winner(winner)
+            })
+        );
+    }
+    #[test]
+    fn test_stat_formulas() {
+        // Level 78 Adamant Garchomp, the games' worked example.
+        let garchomp = MonsterBuilder::new(445)
+            .level(78)
+            .nature(MonsterNature::Adamant)
+            .ivs(MonsterStats {
+                internal: [24, 12, 30, 5, 16, 23],
+            })
+            .evs(MonsterStats {
+                internal: [74, 190, 91, 23, 48, 84],
+            })
+            .build()
+            .unwrap();
+        assert_eq!(garchomp.stats.internal, [289, 278, 193, 171, 135, 171]);
+        assert_eq!(garchomp.hp, 289);
+
+        // Level 50 Timid Pikachu with perfect IVs; Attack floors 67.5 down.
+        let pikachu = MonsterBuilder::new(25)
+            .level(50)
+            .nature(MonsterNature::Timid)
+            .ivs(MonsterStats { internal: [31; 6] })
+            .evs(MonsterStats {
+                internal: [4, 0, 0, 252, 252, 0],
+            })
+            .build()
+            .unwrap();
+        assert_eq!(pikachu.stats.internal, [111, 67, 60, 156, 102, 70]);
+
+        let mut shedinja = Monster::from_dex(292);
+        shedinja.set_level(100);
+        assert_eq!(shedinja.max_hp(), 1);
+
+        // Freshly generated monsters start at full HP.
+        let bulbasaur = Monster::from_dex(1);
+        assert_eq!(bulbasaur.hp, bulbasaur.max_hp());
+        assert_eq!(bulbasaur.hp, bulbasaur.compute_stat(MonsterAttribute::HP));
+    }
+    #[test]
+    fn test_natures() {
+        assert_eq!(
+            MonsterNature::Adamant.increased(),
+            Some(MonsterAttribute::ATT)
+        );
+        assert_eq!(
+            MonsterNature::Adamant.decreased(),
+            Some(MonsterAttribute::SpATT)
+        );
+        assert_eq!(
+            MonsterNature::Timid.modifiers(),
+            Some((MonsterAttribute::SPD, MonsterAttribute::ATT))
+        );
+        assert_eq!(MonsterNature::Adamant.likes(), Some(Flavor::Spicy));
+        assert_eq!(MonsterNature::Adamant.dislikes(), Some(Flavor::Dry));
+        let neutral = [
+            MonsterNature::Bashful,
+            MonsterNature::Docile,
+            MonsterNature::Hardy,
+            MonsterNature::Quirky,
+            MonsterNature::Serious,
+        ];
+        for nature in neutral.iter() {
+            assert!(nature.is_neutral());
+            assert_eq!(nature.modifiers(), None);
+            assert_eq!(nature.likes(), None);
+            let mon = MonsterBuilder::new(25)
+                .level(50)
+                .nature(nature.clone())
+                .ivs(MonsterStats { internal: [31; 6] })
+                .evs(MonsterStats::new())
+                .build()
+                .unwrap();
+            assert_eq!(mon.stats.internal, [110, 75, 60, 110, 70, 70]);
+        }
+    }
+    #[test]
+    fn test_seeded() {
+        let dex = Dex::global().unwrap();
+        let mut a = StdRng::seed_from_u64(42);
+        let mut b = StdRng::seed_from_u64(42);
+        let t = Monster::try_from_dex_with(dex, 25, &mut a).unwrap();
+        let t2 = Monster::try_from_dex_with(dex, 25, &mut b).unwrap();
+        assert_eq!(t, t2);
+        let thunder_shock = t.get_move(0).unwrap();
+        for _ in 0..10 {
+            assert_eq!(t.did_hit(&mut a, 70, 1f32), t2.did_hit(&mut b, 70, 1f32));
+            assert_eq!(
+                t.deal_damage(&mut a, &t2, &thunder_shock, false),
+                t2.deal_damage(&mut b, &t, &thunder_shock, false)
+            );
         }
-        println!("Enemy {} fainted.", t2.base_name);
     }
     #[test]
     fn test_serialize() {
@@ -665,7 +1185,7 @@ mod tests {
         t.combat_status
             .insert(MonsterStatus::BRN | MonsterStatus::BND);
         let json = {
-            if cfg!(debug) {
+            if cfg!(debug_assertions) {
                 let temp = serde_json::to_string_pretty(&t).unwrap();
                 println!("{}", temp);
                 temp
@@ -676,4 +1196,26 @@ mod tests {
         let t2: Monster = serde_json::from_str(&json).unwrap();
         assert_eq!(t, t2);
     }
+    #[test]
+    fn test_deserialize_stats() {
+        let mut t = Monster::from_dex(1);
+        t.set_level(30);
+        t.hp -= 5;
+        // Monsters saved before stats were cached have no stats, or stale ones.
+        let mut value = serde_json::to_value(&t).unwrap();
+        value["stats"] = serde_json::json!({ "internal": [0, 0, 0, 0, 0, 0] });
+        let t2: Monster = serde_json::from_value(value).unwrap();
+        assert_eq!(t2.stats, t.stats);
+        assert_eq!(t2.max_hp(), t.max_hp());
+        assert_eq!(t2.hp, t.hp);
+
+        let mut t3 = t2.clone();
+        t3.set_nature(MonsterNature::Adamant);
+        t3.set_ivs(MonsterStats { internal: [31; 6] });
+        t3.set_evs(MonsterStats {
+            internal: [0, 252, 0, 0, 0, 0],
+        });
+        assert_eq!(t3.stats, t3.computed_stats());
+        assert!(t3.get_stat(MonsterAttribute::ATT) > t2.get_stat(MonsterAttribute::ATT));
+    }
 }
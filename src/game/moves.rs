@@ -1,21 +1,10 @@
+use super::data::{self, error::DataError, result, MovesEntry};
+use super::dex::Dex;
+use super::monster::{MonsterAttribute, MonsterStatus};
 use super::ElementType;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::fs;
-use super::data::MovesEntry;
-
-lazy_static! {
-    pub static ref POKEMON_MOVES: HashMap<usize, PokemonMove> = {
-        let mut map: HashMap<usize, PokemonMove> = HashMap::new();
-        let pjson: Vec<MovesEntry> =
-            serde_json::from_slice(&fs::read("data/moves.json").unwrap()[..]).unwrap();
-        for obj in pjson.iter() {
-            let entry = PokemonMove::from_json(obj);
-            map.insert(entry.id, entry);
-        }
-        map
-    };
-}
+use std::path::Path;
 
 #[repr(u8)]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Eq, Hash)]
@@ -25,7 +14,56 @@ pub enum MoveCategory {
     Status,
 }
 
+impl TryFrom<&str> for MoveCategory {
+    type Error = DataError;
+
+    fn try_from(v: &str) -> result::Result<Self> {
+        match v {
+            "物理" | "Physical" => Ok(MoveCategory::Physical),
+            "特殊" | "Special" => Ok(MoveCategory::Special),
+            "变化" | "Status" => Ok(MoveCategory::Status),
+            _ => Err(DataError::invalid(format!("Unknown move category: {}.", v))),
+        }
+    }
+}
+
+/// The rule used to decide whether a damaging move is Physical or Special.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Eq, Hash, Default)]
+pub enum CategoryMechanic {
+    /// Gen3: the category is decided by the move's element.
+    TypeBased,
+    /// Gen4+: each move has its own category.
+    #[default]
+    PerMove,
+}
+
+/// The rule giving a move its priority bracket when the move data doesn't.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Eq, Hash, Default)]
+pub enum PriorityMechanic {
+    /// Gen3 and Gen4: Protect, Detect, Endure and Follow Me are +3,
+    /// Fake Out and Extreme Speed +1.
+    Gen3To4,
+    /// Gen5: Protect, Detect and Endure are +4, Fake Out, Follow Me and Rage Powder +3,
+    /// Extreme Speed +2, Ally Switch +1, and Wonder Room and Magic Room 0.
+    Gen5,
+    /// Gen6+: as Gen5, but Follow Me, Rage Powder and Ally Switch are +2, and
+    /// Wonder Room and Magic Room -7.
+    #[default]
+    Gen6Plus,
+}
+
 impl MoveCategory {
+    /// Resolve the effective category of a move under the given mechanic.
+    /// Status moves stay Status regardless of the mechanic.
+    pub fn resolve(data: MoveCategory, element: ElementType, mechanic: CategoryMechanic) -> Self {
+        match (data, mechanic) {
+            (MoveCategory::Status, _) => MoveCategory::Status,
+            (data, CategoryMechanic::PerMove) => data,
+            (_, CategoryMechanic::TypeBased) => Self::get_element_category(element),
+        }
+    }
     pub fn get_element_category(element: ElementType) -> Self {
         match element {
             ElementType::Normal
@@ -54,47 +92,279 @@ impl MoveCategory {
 unsafe impl Send for MoveCategory {}
 unsafe impl Sync for MoveCategory {}
 
+/// The secondary effect of a status move.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MoveEffect {
+    /// Inflict a major status on the target.
+    Status(MonsterStatus),
+    /// Change one of the target's stat stages.
+    TargetStage(MonsterAttribute, i8),
+    /// Change one of the user's stat stages.
+    UserStage(MonsterAttribute, i8),
+    /// Reverse the Speed order for five turns, or end an active Trick Room.
+    TrickRoom,
+    /// Raise the user's critical hit stage by 2 until it switches out.
+    FocusEnergy,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct PokemonMove {
     pub id: usize,
     pub name: String,
     pub element: ElementType,
+    /// The effective category under the mechanic this move was loaded with.
     pub category: MoveCategory,
+    /// The move's own category from the move data.
+    pub data_category: MoveCategory,
     pub pp: Option<u8>,
     pub accuracy: Option<u8>,
     pub power: Option<u8>,
+    /// Moves in a higher priority bracket go before moves in a lower one, regardless of Speed.
+    pub priority: i8,
+    /// The move's own priority from the move data, if it has one.
+    pub data_priority: Option<i8>,
 }
 
 impl PokemonMove {
-    pub fn from_json(obj: &MovesEntry) -> PokemonMove {
+    pub fn get_move(id: usize) -> Option<Self> {
+        Self::try_get_move(id).ok()
+    }
+    pub fn try_get_move(id: usize) -> result::Result<Self> {
+        Dex::global()?.get_move(id).cloned()
+    }
+    /// Load a moves file, keyed by move ID.
+    pub fn load<P: AsRef<Path>>(path: P) -> result::Result<HashMap<usize, Self>> {
+        let path = path.as_ref();
+        let pjson: Vec<MovesEntry> = data::load_json(path)?;
+        Self::from_entries(&pjson).map_err(|e| e.in_file(&path.display().to_string()))
+    }
+    pub fn from_entries(entries: &[MovesEntry]) -> result::Result<HashMap<usize, Self>> {
+        let mut map: HashMap<usize, PokemonMove> = HashMap::new();
+        for obj in entries.iter() {
+            let entry = PokemonMove::from_json(obj)?;
+            map.insert(entry.id, entry);
+        }
+        Ok(map)
+    }
+    pub fn from_json(obj: &MovesEntry) -> result::Result<PokemonMove> {
+        Self::from_json_with(
+            obj,
+            CategoryMechanic::default(),
+            PriorityMechanic::default(),
+        )
+    }
+    /// Convert a moves entry, failing on an unknown element type or category.
+    pub fn from_json_with(
+        obj: &MovesEntry,
+        mechanic: CategoryMechanic,
+        priority_mechanic: PriorityMechanic,
+    ) -> result::Result<PokemonMove> {
         let accuracy = obj.accuracy;
         let id = obj.id as usize;
         let name = obj.ename.clone();
         let power = obj.power;
         let pp = obj.pp;
         let element_type = obj._type.clone();
-        let element = ElementType::from(element_type.as_str());
-        let category = MoveCategory::get_element_category(element.clone());
+        let element = ElementType::try_from(element_type.as_str())?;
+        let data_category = MoveCategory::try_from(obj.category.as_str())?;
+        let category = MoveCategory::resolve(data_category.clone(), element.clone(), mechanic);
+        let data_priority = obj.priority;
+        let priority =
+            data_priority.unwrap_or_else(|| Self::default_priority(id, priority_mechanic));
 
-        Self {
+        Ok(Self {
             id,
             name,
             element,
             category,
+            data_category,
             pp,
             accuracy,
             power,
+            priority,
+            data_priority,
+        })
+    }
+    /// The priority bracket of a move under the given mechanic. Most moves are 0.
+    pub fn default_priority(id: usize, mechanic: PriorityMechanic) -> i8 {
+        match (id, mechanic) {
+            (182 | 197 | 203, PriorityMechanic::Gen3To4) => 3, // Protect, Detect, Endure
+            (266, PriorityMechanic::Gen3To4 | PriorityMechanic::Gen5) => 3, // Follow Me
+            (476, PriorityMechanic::Gen5) => 3,                // Rage Powder
+            (245 | 252, PriorityMechanic::Gen3To4) => 1,       // Extreme Speed, Fake Out
+            (502, PriorityMechanic::Gen5) => 1,                // Ally Switch
+            (472 | 478, PriorityMechanic::Gen5) => 0,          // Wonder Room, Magic Room
+            (id, _) => Self::shared_priority(id),
         }
     }
+    /// The Gen6+ priority brackets, which earlier generations mostly share.
+    fn shared_priority(id: usize) -> i8 {
+        match id {
+            270 => 5, // Helping Hand
+            // Protect, Detect, Endure, Magic Coat, Snatch, King's Shield, Spiky Shield,
+            // Baneful Bunker
+            182 | 197 | 203 | 277 | 289 | 588 | 596 | 661 => 4,
+            252 | 469 | 501 => 3, // Fake Out, Wide Guard, Quick Guard
+            // Extreme Speed, Follow Me, Feint, Rage Powder, Ally Switch
+            245 | 266 | 364 | 476 | 502 => 2,
+            // Quick Attack, Mach Punch, Bide, Sucker Punch, Vacuum Wave, Bullet Punch,
+            // Ice Shard, Shadow Sneak, Aqua Jet, Water Shuriken, Accelerock
+            98 | 183 | 117 | 389 | 410 | 418 | 420 | 425 | 453 | 594 | 709 => 1,
+            233 => -1,                 // Vital Throw
+            264 => -3,                 // Focus Punch
+            279 | 419 => -4,           // Revenge, Avalanche
+            68 | 243 => -5,            // Counter, Mirror Coat
+            18 | 46 | 509 | 525 => -6, // Whirlwind, Roar, Circle Throw, Dragon Tail
+            433 | 472 | 478 => -7,     // Trick Room, Wonder Room, Magic Room
+            _ => 0,
+        }
+    }
+    /// The effect of this move, for the status moves the battle engine implements.
+    pub fn effect(&self) -> Option<MoveEffect> {
+        let effect = match self.id {
+            14 => MoveEffect::UserStage(MonsterAttribute::ATT, 2), // Swords Dance
+            28 => MoveEffect::TargetStage(MonsterAttribute::ACC, -1), // Sand Attack
+            39 | 43 => MoveEffect::TargetStage(MonsterAttribute::DEF, -1), // Tail Whip, Leer
+            45 => MoveEffect::TargetStage(MonsterAttribute::ATT, -1), // Growl
+            77 => MoveEffect::Status(MonsterStatus::PSN),          // Poison Powder
+            78 | 86 => MoveEffect::Status(MonsterStatus::PAR),     // Stun Spore, Thunder Wave
+            79 => MoveEffect::Status(MonsterStatus::SLP),          // Sleep Powder
+            81 => MoveEffect::TargetStage(MonsterAttribute::SPD, -2), // String Shot
+            92 => MoveEffect::Status(MonsterStatus::TOX),          // Toxic
+            97 => MoveEffect::UserStage(MonsterAttribute::SPD, 2), // Agility
+            104 => MoveEffect::UserStage(MonsterAttribute::EV, 1), // Double Team
+            106 => MoveEffect::UserStage(MonsterAttribute::DEF, 1), // Harden
+            116 => MoveEffect::FocusEnergy,                        // Focus Energy
+            261 => MoveEffect::Status(MonsterStatus::BRN),         // Will-O-Wisp
+            433 => MoveEffect::TrickRoom,
+            _ => return None,
+        };
+        Some(effect)
+    }
+    /// Whether this move touches its target, triggering abilities like Static.
+    /// Every physical move makes contact except the ranged ones listed here.
+    pub fn makes_contact(&self) -> bool {
+        let ranged = matches!(
+            self.id,
+            40 | 41 | 42 | 131 // Poison Sting, Twineedle, Pin Missile, Spike Cannon
+            | 88 | 157 | 317 | 350 | 439 | 444 // Rock Throw, Rock Slide, Rock Tomb, Rock Blast, Rock Wrecker, Stone Edge
+            | 89 | 90 | 222 | 328 | 523 // Earthquake, Fissure, Magnitude, Sand Tomb, Bulldoze
+            | 125 | 155 | 198 // Bone Club, Bonemerang, Bone Rush
+            | 120 | 153 // Self-Destruct, Explosion
+            | 121 | 140 | 331 | 402 // Egg Bomb, Barrage, Bullet Seed, Seed Bomb
+            | 333 | 420 | 556 | 553 // Icicle Spear, Ice Shard, Icicle Crash, Freeze Shock
+            | 6 | 75 | 143 | 217 | 221 | 251 // Pay Day, Razor Leaf, Sky Attack, Present, Sacred Fire, Beat Up
+            | 363 | 364 | 368 | 374 // Natural Gift, Feint, Metal Burst, Fling
+            | 427 | 441 | 443 | 454 // Psycho Cut, Gunk Shot, Magnet Bomb, Attack Order
+            | 479 | 559 // Smack Down, Fusion Bolt
+        );
+        self.data_category == MoveCategory::Physical && !ranged
+    }
+    /// Re-resolve this move's category under a different mechanic.
+    pub fn set_category_mechanic(&mut self, mechanic: CategoryMechanic) {
+        self.category =
+            MoveCategory::resolve(self.data_category.clone(), self.element.clone(), mechanic);
+    }
+    /// Re-resolve this move's priority under a different mechanic.
+    /// A priority given by the move data is kept.
+    pub fn set_priority_mechanic(&mut self, mechanic: PriorityMechanic) {
+        self.priority = self
+            .data_priority
+            .unwrap_or_else(|| Self::default_priority(self.id, mechanic));
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::game::monster::Monster;
+    use super::{CategoryMechanic, MoveCategory, PokemonMove, PriorityMechanic};
+    use crate::game::{
+        data::{error::DataError, MovesEntry},
+        monster::Monster,
+    };
 
     #[test]
     fn get_move() {
         let monster = Monster::from_dex(1);
         println!("{:?}\n\n{:?}", monster, monster.get_move(0).unwrap());
     }
+
+    #[test]
+    fn category_mechanic() {
+        // Fire Punch is Physical, but Fire was a Special type in Gen3.
+        let mut fire_punch = PokemonMove::get_move(7).unwrap();
+        assert_eq!(fire_punch.category, MoveCategory::Physical);
+        fire_punch.set_category_mechanic(CategoryMechanic::TypeBased);
+        assert_eq!(fire_punch.category, MoveCategory::Special);
+        let mut growl = PokemonMove::get_move(45).unwrap();
+        growl.set_category_mechanic(CategoryMechanic::TypeBased);
+        assert_eq!(growl.category, MoveCategory::Status);
+    }
+
+    #[test]
+    fn invalid_entries() {
+        let entry: MovesEntry = serde_json::from_value(serde_json::json!({
+            "accuracy": 100, "category": "Other", "cname": "", "ename": "Oddity",
+            "id": 9000, "jname": "", "power": 40, "pp": 10, "type": "Normal"
+        }))
+        .unwrap();
+        assert_eq!(
+            PokemonMove::from_entries(&[entry]).unwrap_err(),
+            DataError::invalid("Unknown move category: Other.".to_string())
+        );
+    }
+
+    #[test]
+    fn priority() {
+        assert_eq!(PokemonMove::get_move(33).unwrap().priority, 0);
+        assert_eq!(PokemonMove::get_move(98).unwrap().priority, 1);
+        assert_eq!(PokemonMove::get_move(182).unwrap().priority, 4);
+        assert_eq!(PokemonMove::get_move(433).unwrap().priority, -7);
+
+        let mut fake_out = PokemonMove::get_move(252).unwrap();
+        assert_eq!(fake_out.priority, 3);
+        fake_out.set_priority_mechanic(PriorityMechanic::Gen3To4);
+        assert_eq!(fake_out.priority, 1);
+        let mut follow_me = PokemonMove::get_move(266).unwrap();
+        assert_eq!(follow_me.priority, 2);
+        follow_me.set_priority_mechanic(PriorityMechanic::Gen5);
+        assert_eq!(follow_me.priority, 3);
+        // Moves that never changed keep their bracket.
+        follow_me.set_priority_mechanic(PriorityMechanic::Gen3To4);
+        assert_eq!(follow_me.priority, 3);
+        let mut quick_attack = PokemonMove::get_move(98).unwrap();
+        quick_attack.set_priority_mechanic(PriorityMechanic::Gen3To4);
+        assert_eq!(quick_attack.priority, 1);
+
+        let brackets = [
+            (364, 2),  // Feint
+            (469, 3),  // Wide Guard
+            (476, 2),  // Rage Powder
+            (501, 3),  // Quick Guard
+            (502, 2),  // Ally Switch
+            (509, -6), // Circle Throw
+            (525, -6), // Dragon Tail
+            (472, -7), // Wonder Room
+            (478, -7), // Magic Room
+            (588, 4),  // King's Shield
+            (594, 1),  // Water Shuriken
+            (596, 4),  // Spiky Shield
+        ];
+        for (id, priority) in brackets.iter() {
+            assert_eq!(PokemonMove::get_move(*id).unwrap().priority, *priority);
+        }
+        assert_eq!(
+            PokemonMove::default_priority(661, PriorityMechanic::Gen6Plus),
+            4
+        );
+        assert_eq!(
+            PokemonMove::default_priority(709, PriorityMechanic::Gen6Plus),
+            1
+        );
+        let mut rage_powder = PokemonMove::get_move(476).unwrap();
+        rage_powder.set_priority_mechanic(PriorityMechanic::Gen5);
+        assert_eq!(rage_powder.priority, 3);
+        let mut magic_room = PokemonMove::get_move(478).unwrap();
+        magic_room.set_priority_mechanic(PriorityMechanic::Gen5);
+        assert_eq!(magic_room.priority, 0);
+    }
 }
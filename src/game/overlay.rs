@@ -0,0 +1,234 @@
+use super::{
+    data::{self, error::DataError, result, MovesEntry, PokedexEntry},
+    dex::Dex,
+    moves::PokemonMove,
+    pokedex::PokedexData,
+};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// The table an overlay applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OverlayKind {
+    Species,
+    Moves,
+}
+
+/// The result of applying an overlay to a `Dex`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OverlayReport {
+    /// The name of the applied overlay.
+    pub source: String,
+    /// IDs that did not exist before the overlay.
+    pub added: Vec<usize>,
+    /// IDs that replaced an entry from the base data.
+    pub overridden: Vec<usize>,
+}
+
+impl Dex {
+    /// Load a species overlay file in the `pokedex.json` schema.
+    pub fn load_species_overlay<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+    ) -> result::Result<OverlayReport> {
+        let path = path.as_ref();
+        let entries: Vec<PokedexEntry> = data::load_json(path)?;
+        self.apply_species_overlay(&path.display().to_string(), &entries)
+    }
+    /// Load a moves overlay file in the `moves.json` schema.
+    pub fn load_moves_overlay<P: AsRef<Path>>(&mut self, path: P) -> result::Result<OverlayReport> {
+        let path = path.as_ref();
+        let entries: Vec<MovesEntry> = data::load_json(path)?;
+        self.apply_moves_overlay(&path.display().to_string(), &entries)
+    }
+    /// Add or override species by ID.
+    /// Fails without changing the dex if an entry is invalid or an ID conflicts with
+    /// another overlay.
+    pub fn apply_species_overlay(
+        &mut self,
+        source: &str,
+        entries: &[PokedexEntry],
+    ) -> result::Result<OverlayReport> {
+        let converted = entries
+            .iter()
+            .map(PokedexData::from_json)
+            .collect::<result::Result<Vec<_>>>()
+            .map_err(|e| e.in_file(source))?;
+        let ids: Vec<usize> = converted.iter().map(|entry| entry.id).collect();
+        let report = self.check_overlay(OverlayKind::Species, source, &ids, |dex, id| {
+            dex.species.contains_key(&id)
+        })?;
+        self.record_overlay(OverlayKind::Species, source, &ids);
+        for mut entry in converted.into_iter() {
+            // Overridden species keep their learnset, abilities and metadata.
+            if let Some(existing) = self.species.get(&entry.id) {
+                entry.learnset = existing.learnset.clone();
+                entry.abilities = existing.abilities.clone();
+                entry.info = existing.info.clone();
+            }
+            self.species.insert(entry.id, entry);
+        }
+        Ok(report)
+    }
+    /// Add or override moves by ID.
+    /// Fails without changing the dex if an entry is invalid or an ID conflicts with
+    /// another overlay.
+    pub fn apply_moves_overlay(
+        &mut self,
+        source: &str,
+        entries: &[MovesEntry],
+    ) -> result::Result<OverlayReport> {
+        let converted = entries
+            .iter()
+            .map(|obj| {
+                PokemonMove::from_json_with(obj, self.category_mechanic, self.priority_mechanic)
+            })
+            .collect::<result::Result<Vec<_>>>()
+            .map_err(|e| e.in_file(source))?;
+        let ids: Vec<usize> = converted.iter().map(|entry| entry.id).collect();
+        let report = self.check_overlay(OverlayKind::Moves, source, &ids, |dex, id| {
+            dex.moves.contains_key(&id)
+        })?;
+        self.record_overlay(OverlayKind::Moves, source, &ids);
+        for entry in converted.into_iter() {
+            self.moves.insert(entry.id, entry);
+        }
+        Ok(report)
+    }
+    /// Validate an overlay's IDs, reporting which are added and which are overridden.
+    fn check_overlay<F>(
+        &self,
+        kind: OverlayKind,
+        source: &str,
+        ids: &[usize],
+        exists: F,
+    ) -> result::Result<OverlayReport>
+    where
+        F: Fn(&Dex, usize) -> bool,
+    {
+        let mut seen: HashSet<usize> = HashSet::new();
+        for id in ids.iter() {
+            if !seen.insert(*id) {
+                return Err(DataError::OverlayConflict {
+                    source: source.to_string(),
+                    id: *id,
+                    existing: source.to_string(),
+                });
+            }
+            if let Some(existing) = self.overlay_sources.get(&(kind, *id)) {
+                return Err(DataError::OverlayConflict {
+                    source: source.to_string(),
+                    id: *id,
+                    existing: existing.clone(),
+                });
+            }
+        }
+        let mut report = OverlayReport {
+            source: source.to_string(),
+            ..OverlayReport::default()
+        };
+        for id in ids.iter() {
+            if exists(self, *id) {
+                report.overridden.push(*id);
+            } else {
+                report.added.push(*id);
+            }
+        }
+        Ok(report)
+    }
+    /// Record an overlay as the source of its IDs.
+    fn record_overlay(&mut self, kind: OverlayKind, source: &str, ids: &[usize]) {
+        for id in ids.iter() {
+            self.overlay_sources.insert((kind, *id), source.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::game::{
+        data::{error::DataError, PokedexEntry},
+        dex::Dex,
+        monster::Monster,
+        ElementType,
+    };
+
+    fn species(id: u64, name: &str) -> PokedexEntry {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "name": { "english": name, "japanese": "", "chinese": "", "french": "" },
+            "type": ["Fairy"],
+            "base": {
+                "HP": 80, "Attack": 80, "Defense": 80,
+                "Sp. Attack": 80, "Sp. Defense": 80, "Speed": 80
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn species_overlay() {
+        let mut dex = Dex::global().unwrap().clone();
+        let report = dex
+            .apply_species_overlay(
+                "pack-a",
+                &[species(1, "Bulbafae"), species(5000, "Fakemon")],
+            )
+            .unwrap();
+        assert_eq!(report.added, vec![5000]);
+        assert_eq!(report.overridden, vec![1]);
+        let mon = Monster::try_from_dex_in(&dex, 1).unwrap();
+        assert_eq!(mon.base_name, "Bulbafae");
+        assert_eq!(mon.elements, (ElementType::Fairy, ElementType::Fairy));
+
+        // Redefining an ID from another pack is a conflict and leaves the dex untouched.
+        let err = dex
+            .apply_species_overlay("pack-b", &[species(5001, "Other"), species(5000, "Clash")])
+            .unwrap_err();
+        assert_eq!(
+            err,
+            DataError::OverlayConflict {
+                source: "pack-b".to_string(),
+                id: 5000,
+                existing: "pack-a".to_string(),
+            }
+        );
+        assert!(dex.get_species(5001).is_err());
+        assert!(dex
+            .apply_species_overlay("pack-c", &[species(6000, "A"), species(6000, "B")])
+            .is_err());
+
+        // An invalid entry is an error, and none of the pack is applied.
+        let mut shadow = species(7001, "Shadowmon");
+        shadow._type = vec!["Shadow".to_string()];
+        assert_eq!(
+            dex.apply_species_overlay("pack-d", &[species(7000, "Valid"), shadow])
+                .unwrap_err(),
+            DataError::Parse {
+                path: "pack-d".to_string(),
+                message: "Unknown element type: Shadow.".to_string(),
+            }
+        );
+        assert!(dex.get_species(7000).is_err());
+        assert!(dex
+            .apply_species_overlay("pack-e", &[species(7000, "Valid")])
+            .is_ok());
+    }
+
+    #[test]
+    fn moves_overlay_file() {
+        let path =
+            std::env::temp_dir().join(format!("pokesim_moves_overlay_{}.json", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"[{"accuracy": 90, "category": "特殊", "cname": "", "ename": "Pixie Beam",
+                "id": 9001, "jname": "", "power": 95, "pp": 10, "type": "Fairy"}]"#,
+        )
+        .unwrap();
+        let mut dex = Dex::global().unwrap().clone();
+        let report = dex.load_moves_overlay(&path).unwrap();
+        assert_eq!(report.added, vec![9001]);
+        assert_eq!(dex.get_move(9001).unwrap().name, "Pixie Beam");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,289 @@
+use super::{
+    abilities::AbilitySlots,
+    dex::Dex,
+    monster::{Monster, MonsterGender, MonsterNature},
+    pokedex::PokedexData,
+    species::GenderRatio,
+};
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Unown's species ID.
+pub const UNOWN: usize = 201;
+
+/// Natures in the order a personality value selects them.
+const GEN3_NATURES: [MonsterNature; 25] = [
+    MonsterNature::Hardy,
+    MonsterNature::Lonely,
+    MonsterNature::Brave,
+    MonsterNature::Adamant,
+    MonsterNature::Naughty,
+    MonsterNature::Bold,
+    MonsterNature::Docile,
+    MonsterNature::Relaxed,
+    MonsterNature::Impish,
+    MonsterNature::Lax,
+    MonsterNature::Timid,
+    MonsterNature::Hasty,
+    MonsterNature::Serious,
+    MonsterNature::Jolly,
+    MonsterNature::Naive,
+    MonsterNature::Modest,
+    MonsterNature::Mild,
+    MonsterNature::Quiet,
+    MonsterNature::Bashful,
+    MonsterNature::Rash,
+    MonsterNature::Calm,
+    MonsterNature::Gentle,
+    MonsterNature::Sassy,
+    MonsterNature::Careful,
+    MonsterNature::Quirky,
+];
+
+/// Unown's forms in the order a personality value selects them.
+const UNOWN_FORMS: [char; 28] = [
+    'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S',
+    'T', 'U', 'V', 'W', 'X', 'Y', 'Z', '!', '?',
+];
+
+/// The trainer that originally obtained a monster.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct OriginalTrainer {
+    /// The visible trainer ID.
+    pub id: u16,
+    /// The hidden secret ID.
+    pub secret_id: u16,
+}
+
+impl OriginalTrainer {
+    pub fn new(id: u16, secret_id: u16) -> Self {
+        Self { id, secret_id }
+    }
+    pub fn generate<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Self::new(rng.gen(), rng.gen())
+    }
+}
+
+/// A Gen3 32-bit personality value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Personality(pub u32);
+
+impl Personality {
+    pub fn generate<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Personality(rng.gen())
+    }
+    pub fn nature(&self) -> MonsterNature {
+        GEN3_NATURES[(self.0 % 25) as usize].clone()
+    }
+    /// The value compared with a species' gender threshold.
+    pub fn gender_value(&self) -> u8 {
+        (self.0 & 0xFF) as u8
+    }
+    pub fn gender(&self, ratio: &GenderRatio) -> MonsterGender {
+        match ratio.is_female(self.gender_value()) {
+            Some(true) => MonsterGender::Female,
+            Some(false) => MonsterGender::Male,
+            None => MonsterGender::None,
+        }
+    }
+    /// The regular ability slot, 0 or 1.
+    pub fn ability_slot(&self) -> usize {
+        (self.0 & 1) as usize
+    }
+    /// The ability in this personality's slot; species with one ability always have it.
+    pub fn ability(&self, slots: &AbilitySlots) -> Option<u16> {
+        slots
+            .regular
+            .get(self.ability_slot())
+            .or_else(|| slots.regular.first())
+            .copied()
+    }
+    /// Whether a monster with this personality is shiny for `trainer`.
+    pub fn is_shiny(&self, trainer: &OriginalTrainer) -> bool {
+        let high = (self.0 >> 16) as u16;
+        let low = (self.0 & 0xFFFF) as u16;
+        (trainer.id ^ trainer.secret_id ^ high ^ low) < 8
+    }
+    /// Unown's form: a letter, `!` or `?`.
+    pub fn unown_form(&self) -> char {
+        let pid = self.0;
+        let letter = ((pid & 0x0300_0000) >> 18)
+            | ((pid & 0x0003_0000) >> 12)
+            | ((pid & 0x0000_0300) >> 6)
+            | (pid & 0x0000_0003);
+        UNOWN_FORMS[(letter % 28) as usize]
+    }
+}
+
+/// Traits a generated personality value must produce. Unset traits may be anything.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PersonalityCriteria {
+    pub nature: Option<MonsterNature>,
+    /// The requested gender, checked against `gender_ratio`.
+    pub gender: Option<MonsterGender>,
+    pub gender_ratio: GenderRatio,
+    pub ability_slot: Option<usize>,
+    pub shiny: Option<bool>,
+    pub unown_form: Option<char>,
+}
+
+impl PersonalityCriteria {
+    pub fn matches(&self, pid: Personality, trainer: &OriginalTrainer) -> bool {
+        self.nature.as_ref().is_none_or(|n| pid.nature() == *n)
+            && self
+                .gender
+                .as_ref()
+                .is_none_or(|g| pid.gender(&self.gender_ratio) == *g)
+            && self.ability_slot.is_none_or(|s| pid.ability_slot() == s)
+            && self.shiny.is_none_or(|s| pid.is_shiny(trainer) == s)
+            && self.unown_form.is_none_or(|f| pid.unown_form() == f)
+    }
+    /// Whether any personality value can satisfy these criteria.
+    pub fn is_satisfiable(&self) -> bool {
+        let gender_ok = match (&self.gender, self.gender_ratio) {
+            (None, _) => true,
+            (Some(MonsterGender::None), GenderRatio::Genderless) => true,
+            (Some(_), GenderRatio::Genderless) | (Some(MonsterGender::None), _) => false,
+            (Some(MonsterGender::Female), GenderRatio::Female(0)) => false,
+            (Some(MonsterGender::Male), GenderRatio::Female(8)) => false,
+            _ => true,
+        };
+        let form_ok = self.unown_form.is_none_or(|f| UNOWN_FORMS.contains(&f));
+        gender_ok && form_ok && self.ability_slot.is_none_or(|s| s < 2)
+    }
+    /// Generate a personality value with the requested traits for `trainer`.
+    /// Returns `None` when no value can satisfy the criteria.
+    pub fn generate<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        trainer: &OriginalTrainer,
+    ) -> Option<Personality> {
+        if !self.is_satisfiable() {
+            return None;
+        }
+        loop {
+            let low: u16 = rng.gen();
+            let high: u16 = if self.shiny == Some(true) {
+                // Any high half within 8 of the trainer's XOR makes the monster shiny.
+                trainer.id ^ trainer.secret_id ^ low ^ rng.gen_range(0..8u16)
+            } else {
+                rng.gen()
+            };
+            let pid = Personality(((high as u32) << 16) | low as u32);
+            if self.matches(pid, trainer) {
+                return Some(pid);
+            }
+        }
+    }
+}
+
+impl Monster {
+    /// Whether this monster is shiny for its original trainer.
+    pub fn is_shiny(&self) -> bool {
+        self.personality.is_shiny(&self.trainer)
+    }
+    /// This monster's form if it is an Unown.
+    pub fn unown_form(&self) -> Option<char> {
+        if self.pid == UNOWN {
+            Some(self.personality.unown_form())
+        } else {
+            None
+        }
+    }
+    /// Set this monster's personality value, deriving its nature, gender and ability.
+    pub fn set_personality(&mut self, personality: Personality) {
+        match Dex::global() {
+            Ok(dex) => self.set_personality_in(dex, personality),
+            Err(_) => self.set_personality_with(None, personality),
+        }
+    }
+    /// Set this monster's personality value using species data from the given dex.
+    pub fn set_personality_in(&mut self, dex: &Dex, personality: Personality) {
+        self.set_personality_with(dex.get_species(self.pid).ok(), personality)
+    }
+    fn set_personality_with(&mut self, data: Option<&PokedexData>, personality: Personality) {
+        self.personality = personality;
+        self.nature = personality.nature();
+        let ratio = data
+            .and_then(|data| data.info.as_ref())
+            .map(|info| info.gender_ratio)
+            .unwrap_or_default();
+        self.gender = personality.gender(&ratio);
+        if let Some(data) = data {
+            // Hidden abilities don't depend on the personality value.
+            if self.ability.is_none() || self.ability != data.abilities.hidden {
+                self.ability = personality.ability(&data.abilities);
+            }
+        }
+        self.recompute_stats();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{OriginalTrainer, Personality, PersonalityCriteria};
+    use crate::game::{
+        monster::{Monster, MonsterGender, MonsterNature},
+        species::GenderRatio,
+    };
+    use rand::{prelude::*, rngs::StdRng};
+
+    #[test]
+    fn personality_traits() {
+        let pid = Personality(0x4E6B_C5AB);
+        assert_eq!(pid.nature(), MonsterNature::Adamant);
+        assert_eq!(pid.gender_value(), 0xAB);
+        assert_eq!(pid.gender(&GenderRatio::Female(1)), MonsterGender::Male);
+        assert_eq!(pid.gender(&GenderRatio::Female(8)), MonsterGender::Female);
+        assert_eq!(pid.gender(&GenderRatio::Genderless), MonsterGender::None);
+        assert_eq!(pid.ability_slot(), 1);
+        // 0x4E6B ^ 0xC5AB = 0x8BC0.
+        assert!(pid.is_shiny(&OriginalTrainer::new(0x8BC0, 0)));
+        assert!(pid.is_shiny(&OriginalTrainer::new(0x8BC3, 0x0004)));
+        assert!(!pid.is_shiny(&OriginalTrainer::new(0x8BC8, 0)));
+        assert_eq!(Personality(0).unown_form(), 'A');
+        assert_eq!(Personality(0x0000_0003).unown_form(), 'D');
+
+        // Charmander's ability and gender follow its personality value.
+        let mut charmander = Monster::from_dex(4);
+        charmander.set_personality(Personality(0x0000_001E));
+        assert_eq!(charmander.nature, MonsterNature::Bold);
+        assert_eq!(charmander.gender, MonsterGender::Female);
+        charmander.set_personality(Personality(0x0000_0064));
+        assert_eq!(charmander.gender, MonsterGender::Male);
+    }
+
+    #[test]
+    fn generate_matching() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let trainer = OriginalTrainer::new(12345, 54321);
+        let criteria = PersonalityCriteria {
+            nature: Some(MonsterNature::Adamant),
+            gender: Some(MonsterGender::Female),
+            gender_ratio: GenderRatio::Female(1),
+            ability_slot: Some(1),
+            shiny: Some(true),
+            unown_form: None,
+        };
+        for _ in 0..10 {
+            let pid = criteria.generate(&mut rng, &trainer).unwrap();
+            assert!(criteria.matches(pid, &trainer));
+            assert_eq!(pid.nature(), MonsterNature::Adamant);
+            assert!(pid.is_shiny(&trainer));
+        }
+        let impossible = PersonalityCriteria {
+            gender: Some(MonsterGender::Male),
+            gender_ratio: GenderRatio::Female(8),
+            ..PersonalityCriteria::default()
+        };
+        assert_eq!(impossible.generate(&mut rng, &trainer), None);
+
+        let unown = PersonalityCriteria {
+            unown_form: Some('?'),
+            ..PersonalityCriteria::default()
+        };
+        let mut mon = Monster::from_dex(201);
+        mon.set_personality(unown.generate(&mut rng, &mon.trainer).unwrap());
+        assert_eq!(mon.unown_form(), Some('?'));
+    }
+}
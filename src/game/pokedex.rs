@@ -1,20 +1,11 @@
+use super::abilities::AbilitySlots;
+use super::data::{self, error::DataError, result, PokedexBaseEntry, PokedexEntry};
+use super::dex::Dex;
+use super::learnset::Learnset;
+use super::species::SpeciesInfo;
 use super::{monster::*, ElementType};
 use std::collections::HashMap;
-use std::fs;
-use super::data::PokedexEntry;
-
-lazy_static! {
-    pub static ref POKEDEX: HashMap<usize, PokedexData> = {
-        let mut map: HashMap<usize, PokedexData> = HashMap::new();
-        let pjson: Vec<PokedexEntry> =
-            serde_json::from_slice(&fs::read("data/pokedex.json").unwrap()[..]).unwrap();
-        for obj in pjson.iter() {
-            let entry = PokedexData::from_json(obj);
-            map.insert(entry.id, entry);
-        }
-        map
-    };
-}
+use std::path::Path;
 
 #[derive(Debug, Clone)]
 pub struct PokedexData {
@@ -22,42 +13,119 @@ pub struct PokedexData {
     pub name: String,
     pub element: (ElementType, ElementType),
     pub base: MonsterStats,
+    /// This species' learnset, empty when no learnset data is available.
+    pub learnset: Learnset,
+    /// This species' abilities, empty when no ability data is available.
+    pub abilities: AbilitySlots,
+    /// This species' metadata, if available.
+    pub info: Option<SpeciesInfo>,
 }
 
 impl PokedexData {
     pub fn get_pokemon(id: usize) -> Option<Self> {
-        if let Some(val) = (*POKEDEX).get(&id) {
-            Some(val.clone())
-        } else {
-            None
+        Self::try_get_pokemon(id).ok()
+    }
+    pub fn learnset(&self) -> &Learnset {
+        &self.learnset
+    }
+    pub fn try_get_pokemon(id: usize) -> result::Result<Self> {
+        Dex::global()?.get_species(id).cloned()
+    }
+    /// Load a pokedex file, keyed by species ID.
+    pub fn load<P: AsRef<Path>>(path: P) -> result::Result<HashMap<usize, Self>> {
+        let path = path.as_ref();
+        let pjson: Vec<PokedexEntry> = data::load_json(path)?;
+        Self::from_entries(&pjson).map_err(|e| e.in_file(&path.display().to_string()))
+    }
+    pub fn from_entries(entries: &[PokedexEntry]) -> result::Result<HashMap<usize, Self>> {
+        let mut map: HashMap<usize, PokedexData> = HashMap::new();
+        for obj in entries.iter() {
+            let entry = PokedexData::from_json(obj)?;
+            map.insert(entry.id, entry);
         }
+        Ok(map)
     }
-    pub fn from_json(obj: &PokedexEntry) -> Self {
+    /// Convert a pokedex entry, failing unless it has one or two known element types.
+    pub fn from_json(obj: &PokedexEntry) -> result::Result<Self> {
         let id = obj.id as usize;
         let name = obj.name.english.clone();
-        let elements = obj._type.clone();
-        let elements: (&str, &str) = {
-            if elements.len() == 2 {
-                (elements[0].as_str(), elements[1].as_str())
-            } else {
-                (elements[0].as_str(), elements[0].as_str())
+        let elements: (&str, &str) = match &obj._type[..] {
+            [single] => (single.as_str(), single.as_str()),
+            [first, second] => (first.as_str(), second.as_str()),
+            _ => {
+                return Err(DataError::invalid(format!(
+                    "Species {} must have one or two types.",
+                    id
+                )))
             }
         };
-        let base_stats = [
-            obj.base.hp,
-            obj.base.attack,
-            obj.base.defense,
-            obj.base.speed,
-            obj.base.sp_attack,
-            obj.base.sp_defense,
-        ];
-        Self {
+        Ok(Self {
             id,
             name,
-            element: (ElementType::from(elements.0), ElementType::from(elements.1)),
-            base: MonsterStats {
-                internal: base_stats,
-            },
+            element: (
+                ElementType::try_from(elements.0)?,
+                ElementType::try_from(elements.1)?,
+            ),
+            base: MonsterStats::from(&obj.base),
+            learnset: Learnset::default(),
+            abilities: AbilitySlots::default(),
+            info: None,
+        })
+    }
+}
+
+impl From<&PokedexBaseEntry> for MonsterStats {
+    fn from(obj: &PokedexBaseEntry) -> Self {
+        Self {
+            internal: [
+                obj.hp,
+                obj.attack,
+                obj.defense,
+                obj.speed,
+                obj.sp_attack,
+                obj.sp_defense,
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PokedexData;
+    use crate::game::{data::error::DataError, monster::Monster};
+
+    #[test]
+    fn load_errors() {
+        match PokedexData::load("missing/pokedex.json") {
+            Err(DataError::Io { path, .. }) => assert_eq!(path, "missing/pokedex.json"),
+            other => panic!("Unexpected result: {:?}", other.map(|map| map.len())),
         }
+        assert_eq!(
+            Monster::try_from_dex(9999).unwrap_err(),
+            DataError::UnknownSpecies(9999)
+        );
+        assert_eq!(Monster::try_from_dex(1).unwrap().base_name, "Bulbasaur");
+    }
+
+    #[test]
+    fn invalid_entries() {
+        let path =
+            std::env::temp_dir().join(format!("pokesim_pokedex_{}.json", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"[{"id": 1, "name": {"english": "Shadowmon", "japanese": "", "chinese": "",
+                "french": ""}, "type": ["Shadow"], "base": {"HP": 1, "Attack": 1,
+                "Defense": 1, "Sp. Attack": 1, "Sp. Defense": 1, "Speed": 1}}]"#,
+        )
+        .unwrap();
+        let res = PokedexData::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            res.map(|map| map.len()).unwrap_err(),
+            DataError::Parse {
+                path: path.display().to_string(),
+                message: "Unknown element type: Shadow.".to_string(),
+            }
+        );
     }
 }
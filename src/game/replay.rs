@@ -0,0 +1,329 @@
+use super::{
+    battle::{Action, Battle, BattleSide},
+    critical::CritMechanic,
+    damage::DamageMechanic,
+    dex::Dex,
+    events::BattleEvent,
+    monster::ParalysisMechanic,
+    moves::{CategoryMechanic, PriorityMechanic},
+    rng::RngAlgorithm,
+};
+use serde::{Deserialize, Serialize};
+
+/// The replay format version written by this version of the engine.
+pub const REPLAY_VERSION: u32 = 2;
+
+pub mod error {
+    use crate::game::{
+        battle::error::BattleError,
+        data::error::DataError,
+        events::BattleEvent,
+        moves::{CategoryMechanic, PriorityMechanic},
+    };
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum ReplayError {
+        Data(DataError),
+        /// The replay file could not be parsed or written.
+        Parse(String),
+        UnsupportedVersion(u32),
+        /// The dex resolves move categories or priorities differently from the one
+        /// the battle was recorded with.
+        MechanicMismatch {
+            expected: (CategoryMechanic, PriorityMechanic),
+            actual: (CategoryMechanic, PriorityMechanic),
+        },
+        /// A recorded step was rejected by the battle engine.
+        Battle {
+            step: usize,
+            error: BattleError,
+        },
+        /// The replayed events differ from the recorded ones, starting at `index`.
+        /// `None` means the stream ended early on that side.
+        Diverged {
+            index: usize,
+            expected: Option<BattleEvent>,
+            actual: Option<BattleEvent>,
+        },
+    }
+
+    impl std::fmt::Display for ReplayError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            let msg = match self {
+                ReplayError::Data(e) => e.to_string(),
+                ReplayError::Parse(message) => format!("Invalid replay: {}", message),
+                ReplayError::UnsupportedVersion(version) => {
+                    format!("Unsupported replay version: {}.", version)
+                }
+                ReplayError::MechanicMismatch { expected, actual } => format!(
+                    "Replay was recorded with {:?} but the dex uses {:?}.",
+                    expected, actual
+                ),
+                ReplayError::Battle { step, error } => {
+                    format!("Replay step {} failed: {}", step, error)
+                }
+                ReplayError::Diverged {
+                    index,
+                    expected,
+                    actual,
+                } => format!(
+                    "Replay diverged at event {}: expected {:?}, got {:?}.",
+                    index, expected, actual
+                ),
+            };
+            write!(f, "{}", msg)
+        }
+    }
+
+    impl std::error::Error for ReplayError {}
+
+    impl From<DataError> for ReplayError {
+        fn from(e: DataError) -> Self {
+            ReplayError::Data(e)
+        }
+    }
+}
+
+pub mod result {
+    pub type Result<T> = std::result::Result<T, super::error::ReplayError>;
+}
+
+use error::ReplayError;
+
+/// A single recorded call into a battle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReplayStep {
+    /// A turn taken with `Battle::take_turn`.
+    Turn([Action; 2]),
+    /// A fainted monster replaced with `Battle::replace`.
+    Replace { side: usize, index: usize },
+}
+
+/// Everything needed to play a battle again exactly: the sides before the battle,
+/// the RNG algorithm and seed, the rules in play, every step taken and the events
+/// the battle produced. Create one with `Battle::replay`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    pub sides: [BattleSide; 2],
+    /// Whether the battle was a wild battle.
+    pub wild: bool,
+    pub rng: RngAlgorithm,
+    pub seed: u64,
+    /// The critical hit rules the battle was played with.
+    pub crit_mechanic: CritMechanic,
+    /// The damage formula the battle was played with.
+    pub damage_mechanic: DamageMechanic,
+    /// The paralysis rules the battle was played with.
+    pub paralysis_mechanic: ParalysisMechanic,
+    /// The move category rules of the dex the battle was played with.
+    pub category_mechanic: CategoryMechanic,
+    /// The move priority rules of the dex the battle was played with.
+    pub priority_mechanic: PriorityMechanic,
+    pub steps: Vec<ReplayStep>,
+    /// The events recorded when the battle was played.
+    pub events: Vec<BattleEvent>,
+}
+
+impl Replay {
+    pub fn from_json(json: &str) -> result::Result<Self> {
+        let replay: Self =
+            serde_json::from_str(json).map_err(|e| ReplayError::Parse(e.to_string()))?;
+        if replay.version != REPLAY_VERSION {
+            return Err(ReplayError::UnsupportedVersion(replay.version));
+        }
+        Ok(replay)
+    }
+    pub fn to_json(&self) -> result::Result<String> {
+        serde_json::to_string(self).map_err(|e| ReplayError::Parse(e.to_string()))
+    }
+    /// Play the replay again with the default dex; see `verify_in`.
+    pub fn verify(&self) -> result::Result<Battle> {
+        self.verify_in(Dex::global()?)
+    }
+    /// Play the replay again, looking moves up in the given dex, and check that it
+    /// produces exactly the recorded events. Returns the replayed battle, or the
+    /// first point where the replay diverged.
+    /// The dex must use the category and priority rules the replay was recorded with.
+    pub fn verify_in(&self, dex: &Dex) -> result::Result<Battle> {
+        let expected = (self.category_mechanic, self.priority_mechanic);
+        let actual = (dex.category_mechanic, dex.priority_mechanic);
+        if expected != actual {
+            return Err(ReplayError::MechanicMismatch { expected, actual });
+        }
+        let [first, second] = self.sides.clone();
+        let mut battle = if self.wild {
+            Battle::wild(first, second, self.seed)
+        } else {
+            Battle::new(first, second, self.seed)
+        };
+        battle.crit_mechanic = self.crit_mechanic;
+        battle.damage_mechanic = self.damage_mechanic;
+        battle.paralysis_mechanic = self.paralysis_mechanic;
+        for (step, recorded) in self.steps.iter().enumerate() {
+            let res = match recorded {
+                ReplayStep::Turn(actions) => battle.take_turn_in(dex, actions.clone()),
+                ReplayStep::Replace { side, index } => battle.replace(*side, *index),
+            };
+            if let Err(error) = res {
+                self.check_events(battle.events())?;
+                return Err(ReplayError::Battle { step, error });
+            }
+        }
+        self.check_events(battle.events())?;
+        if battle.events().len() != self.events.len() {
+            let index = battle.events().len().min(self.events.len());
+            return Err(ReplayError::Diverged {
+                index,
+                expected: self.events.get(index).cloned(),
+                actual: battle.events().get(index).cloned(),
+            });
+        }
+        Ok(battle)
+    }
+    /// Check replayed events against the recorded ones, up to the shorter stream.
+    fn check_events(&self, events: &[BattleEvent]) -> result::Result<()> {
+        let diverged = self
+            .events
+            .iter()
+            .zip(events.iter())
+            .position(|(expected, actual)| expected != actual);
+        match diverged {
+            Some(index) => Err(ReplayError::Diverged {
+                index,
+                expected: Some(self.events[index].clone()),
+                actual: Some(events[index].clone()),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{error::ReplayError, Replay, ReplayStep, REPLAY_VERSION};
+    use crate::game::{
+        battle::{error::BattleError, Action, Battle, BattleSide},
+        damage::DamageMechanic,
+        dex::Dex,
+        events::BattleEvent,
+        monster::Monster,
+        moves::{CategoryMechanic, PriorityMechanic},
+    };
+
+    fn team(species: &[usize]) -> BattleSide {
+        let team = species
+            .iter()
+            .map(|id| {
+                let mut mon = Monster::from_dex(*id);
+                mon.set_level(20);
+                mon.moves = (33, 45, 0, 0);
+                mon
+            })
+            .collect();
+        BattleSide::new(team)
+    }
+
+    #[test]
+    fn replay_roundtrip() {
+        let mut battle = Battle::new(team(&[1, 7]), team(&[4, 25]), 99);
+        battle.damage_mechanic = DamageMechanic::Gen3;
+        while battle.outcome().is_none() {
+            for side in 0..2 {
+                if battle.needs_switch(side) {
+                    battle.replace(side, 1).unwrap();
+                }
+            }
+            let slot = (battle.turn % 2) as usize;
+            battle
+                .take_turn([Action::Move(slot), Action::Move(0)])
+                .unwrap();
+        }
+        let replay = battle.replay();
+        assert_eq!(replay.damage_mechanic, DamageMechanic::Gen3);
+        assert!(replay
+            .steps
+            .iter()
+            .any(|step| matches!(step, ReplayStep::Replace { .. })));
+
+        let json = replay.to_json().unwrap();
+        let parsed = Replay::from_json(&json).unwrap();
+        assert_eq!(parsed, replay);
+        let replayed = parsed.verify().unwrap();
+        assert_eq!(replayed.events(), battle.events());
+        assert_eq!(replayed.sides, battle.sides);
+    }
+
+    #[test]
+    fn divergence() {
+        let mut battle = Battle::new(team(&[1]), team(&[4]), 5);
+        battle
+            .take_turn([Action::Move(0), Action::Move(0)])
+            .unwrap();
+        let replay = battle.replay();
+
+        // A different seed changes the rolls.
+        let reseeded = Replay {
+            seed: 6,
+            ..replay.clone()
+        };
+        assert!(matches!(
+            reseeded.verify(),
+            Err(ReplayError::Diverged { .. })
+        ));
+
+        let mut tampered = replay.clone();
+        let last = tampered.events.len() - 1;
+        tampered.events[last] = BattleEvent::TurnStarted { turn: 9 };
+        assert_eq!(
+            tampered.verify().unwrap_err(),
+            ReplayError::Diverged {
+                index: last,
+                expected: Some(BattleEvent::TurnStarted { turn: 9 }),
+                actual: Some(battle.events()[last].clone()),
+            }
+        );
+
+        let mut extra = replay.clone();
+        extra.events.push(BattleEvent::TurnStarted { turn: 2 });
+        assert!(matches!(
+            extra.verify(),
+            Err(ReplayError::Diverged { actual: None, .. })
+        ));
+
+        let mut invalid = replay.clone();
+        invalid
+            .steps
+            .push(ReplayStep::Turn([Action::Move(3), Action::Move(0)]));
+        assert!(matches!(
+            invalid.verify(),
+            Err(ReplayError::Battle { step: 1, .. })
+        ));
+
+        let mut gen3 = Dex::global().unwrap().clone();
+        gen3.set_priority_mechanic(PriorityMechanic::Gen3To4);
+        assert_eq!(
+            replay.verify_in(&gen3).unwrap_err(),
+            ReplayError::MechanicMismatch {
+                expected: (CategoryMechanic::PerMove, PriorityMechanic::Gen6Plus),
+                actual: (CategoryMechanic::PerMove, PriorityMechanic::Gen3To4),
+            }
+        );
+        let mut switched = battle.clone();
+        assert_eq!(
+            switched.take_turn_in(&gen3, [Action::Move(0), Action::Move(0)]),
+            Err(BattleError::MechanicsChanged)
+        );
+
+        let mut json = replay.to_json().unwrap();
+        json = json.replacen(
+            &format!("\"version\":{}", REPLAY_VERSION),
+            "\"version\":0",
+            1,
+        );
+        assert_eq!(
+            Replay::from_json(&json),
+            Err(ReplayError::UnsupportedVersion(0))
+        );
+    }
+}
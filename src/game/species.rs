@@ -0,0 +1,246 @@
+use super::{
+    data::{error::DataError, result, SpeciesInfoEntry},
+    monster::MonsterStats,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The chance of a species being female.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GenderRatio {
+    Genderless,
+    /// The chance of being female, in eighths.
+    Female(u8),
+}
+
+impl Default for GenderRatio {
+    fn default() -> Self {
+        GenderRatio::Female(4)
+    }
+}
+
+impl GenderRatio {
+    /// Convert a gender rate in eighths, with -1 for genderless species.
+    pub fn from_rate(rate: i8) -> Self {
+        match rate {
+            r if r < 0 => GenderRatio::Genderless,
+            r => GenderRatio::Female(r.min(8) as u8),
+        }
+    }
+    /// The Gen3 threshold compared with the low byte of a personality value.
+    /// `None` for genderless species.
+    pub fn threshold(&self) -> Option<u8> {
+        match self {
+            GenderRatio::Genderless => None,
+            GenderRatio::Female(0) => Some(0),
+            GenderRatio::Female(8) => Some(254),
+            GenderRatio::Female(eighths) => Some(eighths * 32 - 1),
+        }
+    }
+    /// Whether a monster is female for a given gender value, such as the
+    /// low byte of its personality value. `None` for genderless species.
+    pub fn is_female(&self, value: u8) -> Option<bool> {
+        match self {
+            GenderRatio::Genderless => None,
+            GenderRatio::Female(0) => Some(false),
+            GenderRatio::Female(8) => Some(true),
+            _ => self.threshold().map(|threshold| value < threshold),
+        }
+    }
+}
+
+/// How much experience a species needs to level up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GrowthRate {
+    Erratic,
+    Fast,
+    #[default]
+    MediumFast,
+    MediumSlow,
+    Slow,
+    Fluctuating,
+}
+
+impl TryFrom<&str> for GrowthRate {
+    type Error = DataError;
+
+    fn try_from(v: &str) -> result::Result<Self> {
+        match v {
+            "erratic" => Ok(GrowthRate::Erratic),
+            "fast" => Ok(GrowthRate::Fast),
+            "medium-fast" => Ok(GrowthRate::MediumFast),
+            "medium-slow" => Ok(GrowthRate::MediumSlow),
+            "slow" => Ok(GrowthRate::Slow),
+            "fluctuating" => Ok(GrowthRate::Fluctuating),
+            _ => Err(DataError::invalid(format!("Unknown growth rate: {}.", v))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EggGroup {
+    Monster,
+    Water1,
+    Bug,
+    Flying,
+    Field,
+    Fairy,
+    Grass,
+    HumanLike,
+    Water3,
+    Mineral,
+    Amorphous,
+    Water2,
+    Ditto,
+    Dragon,
+    /// Species that can't breed.
+    Undiscovered,
+}
+
+impl TryFrom<&str> for EggGroup {
+    type Error = DataError;
+
+    fn try_from(v: &str) -> result::Result<Self> {
+        let group = match v {
+            "Monster" => EggGroup::Monster,
+            "Water1" => EggGroup::Water1,
+            "Bug" => EggGroup::Bug,
+            "Flying" => EggGroup::Flying,
+            "Field" => EggGroup::Field,
+            "Fairy" => EggGroup::Fairy,
+            "Grass" => EggGroup::Grass,
+            "HumanLike" => EggGroup::HumanLike,
+            "Water3" => EggGroup::Water3,
+            "Mineral" => EggGroup::Mineral,
+            "Amorphous" => EggGroup::Amorphous,
+            "Water2" => EggGroup::Water2,
+            "Ditto" => EggGroup::Ditto,
+            "Dragon" => EggGroup::Dragon,
+            "Undiscovered" => EggGroup::Undiscovered,
+            _ => return Err(DataError::invalid(format!("Unknown egg group: {}.", v))),
+        };
+        Ok(group)
+    }
+}
+
+/// Species metadata beyond names, types and base stats.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpeciesInfo {
+    pub gender_ratio: GenderRatio,
+    pub catch_rate: u8,
+    /// The base experience yielded when defeated.
+    pub base_exp: u16,
+    /// The EVs yielded when defeated.
+    pub ev_yield: MonsterStats,
+    pub growth_rate: GrowthRate,
+    pub egg_groups: Vec<EggGroup>,
+    /// Height in decimetres.
+    pub height: u16,
+    /// Weight in hectograms.
+    pub weight: u16,
+    /// The species this one evolves from, if any.
+    #[serde(default)]
+    pub evolves_from: Option<usize>,
+}
+
+impl SpeciesInfo {
+    pub fn from_json(obj: &SpeciesInfoEntry) -> result::Result<Self> {
+        Ok(Self {
+            gender_ratio: GenderRatio::from_rate(obj.gender_rate),
+            catch_rate: obj.catch_rate,
+            base_exp: obj.base_exp,
+            ev_yield: MonsterStats::from(&obj.ev_yield),
+            growth_rate: GrowthRate::try_from(obj.growth_rate.as_str())?,
+            egg_groups: obj
+                .egg_groups
+                .iter()
+                .map(|group| EggGroup::try_from(group.as_str()))
+                .collect::<result::Result<_>>()?,
+            height: obj.height,
+            weight: obj.weight,
+            evolves_from: obj.evolves_from.map(|id| id as usize),
+        })
+    }
+    pub fn from_entries(entries: &[SpeciesInfoEntry]) -> result::Result<HashMap<usize, Self>> {
+        let mut map: HashMap<usize, SpeciesInfo> = HashMap::new();
+        for obj in entries.iter() {
+            map.insert(obj.id as usize, SpeciesInfo::from_json(obj)?);
+        }
+        Ok(map)
+    }
+    /// Whether this species can produce eggs with `other`.
+    /// Ditto breeds with any species that can breed, except another Ditto.
+    pub fn can_breed_with(&self, other: &SpeciesInfo) -> bool {
+        let undiscovered = |info: &SpeciesInfo| info.egg_groups.contains(&EggGroup::Undiscovered);
+        let ditto = |info: &SpeciesInfo| info.egg_groups.contains(&EggGroup::Ditto);
+        if undiscovered(self) || undiscovered(other) {
+            return false;
+        }
+        if ditto(self) || ditto(other) {
+            return ditto(self) != ditto(other);
+        }
+        let genders_compatible = match (self.gender_ratio, other.gender_ratio) {
+            (GenderRatio::Genderless, _) | (_, GenderRatio::Genderless) => false,
+            // One parent must be able to be female and the other male.
+            (GenderRatio::Female(a), GenderRatio::Female(b)) => {
+                (a > 0 && b < 8) || (b > 0 && a < 8)
+            }
+        };
+        genders_compatible
+            && self
+                .egg_groups
+                .iter()
+                .any(|group| other.egg_groups.contains(group))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{EggGroup, GenderRatio, GrowthRate};
+    use crate::game::{
+        monster::{Monster, MonsterAttribute, MonsterGender},
+        pokedex::PokedexData,
+    };
+
+    #[test]
+    fn species_info() {
+        let pikachu = PokedexData::get_pokemon(25).unwrap();
+        let info = pikachu.info.as_ref().unwrap();
+        assert_eq!(info.gender_ratio, GenderRatio::Female(4));
+        assert_eq!(info.catch_rate, 190);
+        assert_eq!(info.ev_yield.get(MonsterAttribute::SPD), 2);
+        assert_eq!(info.growth_rate, GrowthRate::MediumFast);
+        assert_eq!((info.height, info.weight), (4, 60));
+
+        let chansey = PokedexData::get_pokemon(113).unwrap();
+        let gyarados = PokedexData::get_pokemon(130).unwrap();
+        let happiny = PokedexData::get_pokemon(440).unwrap();
+        let chansey = chansey.info.as_ref().unwrap();
+        assert!(chansey.can_breed_with(info));
+        assert!(!chansey.can_breed_with(chansey));
+        assert!(!happiny.info.as_ref().unwrap().can_breed_with(info));
+        assert!(gyarados
+            .info
+            .as_ref()
+            .unwrap()
+            .can_breed_with(PokedexData::get_pokemon(6).unwrap().info.as_ref().unwrap()));
+
+        assert_eq!(GenderRatio::Female(1).threshold(), Some(31));
+        assert_eq!(GenderRatio::Female(1).is_female(30), Some(true));
+        assert_eq!(GenderRatio::Female(1).is_female(31), Some(false));
+        for _ in 0..20 {
+            assert_eq!(Monster::from_dex(113).gender, MonsterGender::Female);
+            assert_eq!(Monster::from_dex(292).gender, MonsterGender::None);
+            assert_eq!(Monster::from_dex(81).gender, MonsterGender::None);
+            assert_eq!(Monster::from_dex(128).gender, MonsterGender::Male);
+        }
+
+        let meltan = PokedexData::get_pokemon(808).unwrap();
+        assert_eq!(
+            meltan.info.as_ref().unwrap().gender_ratio,
+            GenderRatio::Genderless
+        );
+        assert!(GrowthRate::try_from("bogus").is_err());
+        assert!(EggGroup::try_from("bogus").is_err());
+    }
+}
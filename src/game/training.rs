@@ -0,0 +1,187 @@
+use super::{
+    dex::Dex,
+    monster::{Monster, MonsterAttribute, MonsterStats, MAX_EV, MAX_EV_TOTAL},
+};
+
+pub const MACHO_BRACE: u16 = 215;
+pub const POWER_BRACER: u16 = 289;
+pub const POWER_BELT: u16 = 290;
+pub const POWER_LENS: u16 = 291;
+pub const POWER_BAND: u16 = 292;
+pub const POWER_ANKLET: u16 = 293;
+pub const POWER_WEIGHT: u16 = 294;
+
+pub const POMEG_BERRY: u16 = 169;
+pub const KELPSY_BERRY: u16 = 170;
+pub const QUALOT_BERRY: u16 = 171;
+pub const HONDEW_BERRY: u16 = 172;
+pub const GREPA_BERRY: u16 = 173;
+pub const TAMATO_BERRY: u16 = 174;
+
+/// EVs added to its stat by a held Power item.
+pub const POWER_ITEM_BONUS: u8 = 4;
+
+/// EVs removed by a reducing berry.
+pub const BERRY_EV_REDUCTION: u8 = 10;
+
+/// The stat a Power item adds EVs to.
+pub fn power_item_stat(item: u16) -> Option<MonsterAttribute> {
+    match item {
+        POWER_WEIGHT => Some(MonsterAttribute::HP),
+        POWER_BRACER => Some(MonsterAttribute::ATT),
+        POWER_BELT => Some(MonsterAttribute::DEF),
+        POWER_ANKLET => Some(MonsterAttribute::SPD),
+        POWER_LENS => Some(MonsterAttribute::SpATT),
+        POWER_BAND => Some(MonsterAttribute::SpDEF),
+        _ => None,
+    }
+}
+
+/// The stat a reducing berry lowers EVs of.
+pub fn reducing_berry_stat(item: u16) -> Option<MonsterAttribute> {
+    match item {
+        POMEG_BERRY => Some(MonsterAttribute::HP),
+        KELPSY_BERRY => Some(MonsterAttribute::ATT),
+        QUALOT_BERRY => Some(MonsterAttribute::DEF),
+        HONDEW_BERRY => Some(MonsterAttribute::SpATT),
+        GREPA_BERRY => Some(MonsterAttribute::SpDEF),
+        TAMATO_BERRY => Some(MonsterAttribute::SPD),
+        _ => None,
+    }
+}
+
+impl Monster {
+    /// Add EVs to one stat, up to 252 in the stat and 510 in total, and recompute stats.
+    /// Returns the EVs actually added.
+    pub fn add_evs(&mut self, attr: MonsterAttribute, amount: u8) -> u8 {
+        let current = self.ev.get(attr.clone());
+        let room = (MAX_EV - current.min(MAX_EV)) as u16;
+        let total_room = MAX_EV_TOTAL.saturating_sub(self.ev.total());
+        let gained = (amount as u16).min(room).min(total_room) as u8;
+        self.ev.set(attr, current + gained);
+        self.recompute_stats();
+        gained
+    }
+    /// Award the EVs for defeating a monster with the given EV yield.
+    /// The held Macho Brace doubles the yield and Power items add 4 to their stat,
+    /// then Pokérus doubles the result. Returns the EVs actually gained.
+    pub fn gain_evs(&mut self, ev_yield: &MonsterStats) -> MonsterStats {
+        let mut earned = [0u16; 6];
+        for (i, val) in earned.iter_mut().enumerate() {
+            *val = ev_yield.internal[i] as u16;
+        }
+        match self.held_item {
+            Some(MACHO_BRACE) => earned.iter_mut().for_each(|val| *val *= 2),
+            Some(item) => {
+                if let Some(attr) = power_item_stat(item) {
+                    earned[attr as usize] += POWER_ITEM_BONUS as u16;
+                }
+            }
+            None => (),
+        }
+        if self.pokerus {
+            earned.iter_mut().for_each(|val| *val *= 2);
+        }
+        let mut gained = MonsterStats::new();
+        for (i, val) in earned.iter().enumerate() {
+            let attr = MonsterAttribute::from(i as u8);
+            let added = self.add_evs(attr.clone(), (*val).min(u8::MAX as u16) as u8);
+            gained.set(attr, added);
+        }
+        gained
+    }
+    /// Award the EVs for defeating a monster of `species`.
+    pub fn gain_evs_from(&mut self, species: usize) -> MonsterStats {
+        match Dex::global() {
+            Ok(dex) => self.gain_evs_from_in(dex, species),
+            Err(_) => MonsterStats::new(),
+        }
+    }
+    /// Award the EVs for defeating a monster of `species` in the given dex.
+    /// Species without metadata yield no EVs.
+    pub fn gain_evs_from_in(&mut self, dex: &Dex, species: usize) -> MonsterStats {
+        let ev_yield = dex
+            .get_species(species)
+            .ok()
+            .and_then(|data| data.info.as_ref())
+            .map(|info| info.ev_yield.clone());
+        match ev_yield {
+            Some(ev_yield) => self.gain_evs(&ev_yield),
+            None => MonsterStats::new(),
+        }
+    }
+    /// Feed this monster a reducing berry, lowering one stat's EVs by 10
+    /// and raising friendship. Returns false if the berry had no effect.
+    pub fn use_reducing_berry(&mut self, item: u16) -> bool {
+        let attr = match reducing_berry_stat(item) {
+            Some(attr) => attr,
+            None => return false,
+        };
+        let current = self.ev.get(attr.clone());
+        if current == 0 && self.friendship == u8::MAX {
+            return false;
+        }
+        self.ev
+            .set(attr, current.saturating_sub(BERRY_EV_REDUCTION));
+        self.recompute_stats();
+        let bonus = match self.friendship {
+            0..=99 => 10,
+            100..=199 => 5,
+            _ => 2,
+        };
+        self.friendship = self.friendship.saturating_add(bonus);
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game::monster::MonsterStats;
+    use rand::{prelude::*, rngs::StdRng};
+
+    #[test]
+    fn ev_caps() {
+        let mut mon = Monster::from_dex(1);
+        assert_eq!(mon.add_evs(MonsterAttribute::ATT, 250), 250);
+        assert_eq!(mon.add_evs(MonsterAttribute::ATT, 10), 2);
+        assert_eq!(mon.add_evs(MonsterAttribute::SPD, 252), 252);
+        // Only 6 EVs remain before the total cap.
+        assert_eq!(mon.add_evs(MonsterAttribute::HP, 100), 6);
+        assert_eq!(mon.ev.total(), 510);
+
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..1000 {
+            let ev = MonsterStats::random_ev(&mut rng);
+            assert!(ev.total() <= 510);
+            assert!(ev.internal.iter().all(|val| *val <= 252));
+        }
+    }
+
+    #[test]
+    fn ev_training() {
+        // Pikachu yields 2 Speed EVs.
+        let mut mon = Monster::from_dex(1);
+        let gained = mon.gain_evs_from(25);
+        assert_eq!(gained.get(MonsterAttribute::SPD), 2);
+
+        mon.held_item = Some(POWER_BRACER);
+        mon.pokerus = true;
+        let gained = mon.gain_evs_from(25);
+        assert_eq!(gained.get(MonsterAttribute::SPD), 4);
+        assert_eq!(gained.get(MonsterAttribute::ATT), 8);
+
+        mon.held_item = Some(MACHO_BRACE);
+        let gained = mon.gain_evs_from(25);
+        assert_eq!(gained.get(MonsterAttribute::SPD), 8);
+        assert_eq!(mon.ev.get(MonsterAttribute::SPD), 14);
+
+        let friendship = mon.friendship;
+        assert!(mon.use_reducing_berry(TAMATO_BERRY));
+        assert_eq!(mon.ev.get(MonsterAttribute::SPD), 4);
+        assert_eq!(mon.friendship, friendship + 10);
+        assert!(mon.use_reducing_berry(TAMATO_BERRY));
+        assert_eq!(mon.ev.get(MonsterAttribute::SPD), 0);
+        assert!(!mon.use_reducing_berry(234));
+    }
+}